@@ -0,0 +1,71 @@
+//! Runs perft on a position and prints the count for each move, like `go perft` in Stockfish
+//!
+//! Usage: `fritiofr-perft [--threads n] [--hash mb] [fen] <depth>`, the FEN can be one quoted
+//! argument or split over several arguments. Without a FEN the starting position is used. By
+//! default one thread is used for each CPU and there is no cache.
+
+use std::process::ExitCode;
+
+use fritiofr_chess::{Game, Perft};
+
+const USAGE: &str = "Usage: fritiofr-perft [--threads n] [--hash mb] [fen] <depth>";
+
+fn main() -> ExitCode {
+    let mut threads = 0;
+    let mut cache_size = 0;
+    let mut args = vec![];
+
+    let mut input = std::env::args().skip(1);
+    while let Some(arg) = input.next() {
+        let option = match arg.as_str() {
+            "--threads" => &mut threads,
+            "--hash" => &mut cache_size,
+            _ => {
+                args.push(arg);
+                continue;
+            }
+        };
+
+        let Some(value) = input.next().and_then(|value| value.parse::<usize>().ok()) else {
+            eprintln!("{} needs a number\n{}", arg, USAGE);
+            return ExitCode::FAILURE;
+        };
+        *option = value;
+    }
+
+    let Some(depth) = args.pop() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+    let Ok(depth) = depth.parse::<u32>() else {
+        eprintln!("Invalid depth: {}\n{}", depth, USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let game = if args.is_empty() {
+        Game::start_pos()
+    } else {
+        match Game::from_fen(&args.join(" ")) {
+            Ok(game) => game,
+            Err(err) => {
+                eprintln!("Invalid FEN: {}", err);
+                return ExitCode::FAILURE;
+            }
+        }
+    };
+
+    let result = Perft::new(depth)
+        .set_threads(threads)
+        .set_cache_size(cache_size)
+        .run(&game);
+
+    for (mv, count) in &result.divide {
+        println!("{}: {}", mv, count);
+    }
+
+    println!("\nNodes searched: {}", result.nodes);
+    println!("Time: {} ms", result.elapsed.as_millis());
+    println!("Nodes/second: {}", result.nps());
+
+    ExitCode::SUCCESS
+}
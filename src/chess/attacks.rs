@@ -0,0 +1,354 @@
+//! Precomputed attack tables
+//!
+//! Knights, kings and pawns attack the same squares no matter what else is on the board, so their
+//! attacks are looked up in tables built at compile time. Rooks, bishops and queens are blocked by
+//! other pieces, their attacks are looked up in magic bitboard tables that are built the first
+//! time they're used.
+//!
+//! # Examples
+//! ```
+//! use fritiofr_chess::{attacks, Bitboard, Square};
+//!
+//! let blockers = Bitboard::from_iter([Square::E6, Square::C4]);
+//! let rook = attacks::rook_attacks(Square::E4, blockers);
+//!
+//! assert!(rook.contains(Square::E6));
+//! assert!(!rook.contains(Square::E7));
+//! assert!(rook.contains(Square::C4));
+//! assert!(!rook.contains(Square::B4));
+//! assert_eq!(attacks::knight_attacks(Square::A1).count(), 2);
+//! ```
+
+use std::sync::OnceLock;
+
+use crate::{Bitboard, Color, File, Rank, Square};
+
+const KNIGHT_OFFSETS: [(i32, i32); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+const KING_OFFSETS: [(i32, i32); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+const ROOK_DIRS: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+const BISHOP_DIRS: [(i32, i32); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+static KNIGHT_ATTACKS: [Bitboard; 64] = leaper_table(&KNIGHT_OFFSETS);
+static KING_ATTACKS: [Bitboard; 64] = leaper_table(&KING_OFFSETS);
+static PAWN_ATTACKS: [[Bitboard; 64]; 2] = [
+    leaper_table(&[(-1, 1), (1, 1)]),
+    leaper_table(&[(-1, -1), (1, -1)]),
+];
+
+/// Returns the squares a pawn of a color attacks, which are the squares it can capture on
+pub fn pawn_attacks(color: Color, square: Square) -> Bitboard {
+    PAWN_ATTACKS[color as usize][square.index()]
+}
+
+/// Returns the squares a knight attacks
+pub fn knight_attacks(square: Square) -> Bitboard {
+    KNIGHT_ATTACKS[square.index()]
+}
+
+/// Returns the squares a king attacks
+pub fn king_attacks(square: Square) -> Bitboard {
+    KING_ATTACKS[square.index()]
+}
+
+/// Returns the squares a rook attacks, up to and including the first piece in each direction
+///
+/// # Arguments
+/// * `square` - The square of the rook
+/// * `occupied` - Every piece on the board, the color of the pieces doesn't matter
+pub fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    let tables = sliding_tables();
+
+    tables.attacks[tables.rook[square.index()].index(occupied)]
+}
+
+/// Returns the squares a bishop attacks, up to and including the first piece in each direction
+///
+/// # Arguments
+/// * `square` - The square of the bishop
+/// * `occupied` - Every piece on the board, the color of the pieces doesn't matter
+pub fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    let tables = sliding_tables();
+
+    tables.attacks[tables.bishop[square.index()].index(occupied)]
+}
+
+/// Returns the squares a queen attacks, up to and including the first piece in each direction
+///
+/// # Arguments
+/// * `square` - The square of the queen
+/// * `occupied` - Every piece on the board, the color of the pieces doesn't matter
+pub fn queen_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    rook_attacks(square, occupied) | bishop_attacks(square, occupied)
+}
+
+/// Returns the squares strictly between two squares on the same rank, file or diagonal, empty if
+/// the squares aren't on a line
+pub fn between(a: Square, b: Square) -> Bitboard {
+    let (a_bb, b_bb) = (Bitboard::from(a), Bitboard::from(b));
+
+    if rook_attacks(a, Bitboard::EMPTY).contains(b) {
+        rook_attacks(a, b_bb) & rook_attacks(b, a_bb)
+    } else if bishop_attacks(a, Bitboard::EMPTY).contains(b) {
+        bishop_attacks(a, b_bb) & bishop_attacks(b, a_bb)
+    } else {
+        Bitboard::EMPTY
+    }
+}
+
+/// Returns every square on the rank, file or diagonal that goes through two squares, from edge
+/// to edge, empty if the squares aren't on a line
+pub fn line(a: Square, b: Square) -> Bitboard {
+    let (a_bb, b_bb) = (Bitboard::from(a), Bitboard::from(b));
+
+    if rook_attacks(a, Bitboard::EMPTY).contains(b) {
+        (rook_attacks(a, Bitboard::EMPTY) & rook_attacks(b, Bitboard::EMPTY)) | a_bb | b_bb
+    } else if bishop_attacks(a, Bitboard::EMPTY).contains(b) {
+        (bishop_attacks(a, Bitboard::EMPTY) & bishop_attacks(b, Bitboard::EMPTY)) | a_bb | b_bb
+    } else {
+        Bitboard::EMPTY
+    }
+}
+
+/// Internal helper that builds a table of the squares a piece that moves one step attacks
+const fn leaper_table(offsets: &[(i32, i32)]) -> [Bitboard; 64] {
+    let mut table = [Bitboard::EMPTY; 64];
+
+    let mut square = 0;
+    while square < 64 {
+        let file = square as i32 % 8;
+        let rank = square as i32 / 8;
+
+        let mut bits = 0;
+        let mut i = 0;
+        while i < offsets.len() {
+            let (to_file, to_rank) = (file + offsets[i].0, rank + offsets[i].1);
+
+            if to_file >= 0 && to_file < 8 && to_rank >= 0 && to_rank < 8 {
+                bits |= 1 << (to_rank * 8 + to_file);
+            }
+            i += 1;
+        }
+
+        table[square] = Bitboard(bits);
+        square += 1;
+    }
+
+    table
+}
+
+/// Internal helper that walks the rays of a sliding piece, used to fill the magic tables
+fn sliding_attacks(square: Square, occupied: u64, dirs: &[(i32, i32)]) -> u64 {
+    let mut attacks = 0;
+
+    for (file_dir, rank_dir) in dirs {
+        let mut current = square;
+
+        while let Some(next) = current.offset(*file_dir as isize, *rank_dir as isize) {
+            attacks |= 1 << next.index();
+
+            if occupied & (1 << next.index()) != 0 {
+                break;
+            }
+            current = next;
+        }
+    }
+
+    attacks
+}
+
+/// The magic multiplier for a square, which maps every relevant occupancy to a unique index
+#[derive(Debug, Clone, Copy, Default)]
+struct Magic {
+    /// The squares that can block the piece, without the edges of the board
+    mask: u64,
+    magic: u64,
+    shift: u32,
+    /// Where the attacks of the square start in `SlidingTables::attacks`
+    offset: usize,
+}
+
+impl Magic {
+    fn index(&self, occupied: Bitboard) -> usize {
+        self.offset + ((occupied.0 & self.mask).wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+/// Internal struct that holds the magics for rooks and bishops and the attacks they point to
+struct SlidingTables {
+    rook: [Magic; 64],
+    bishop: [Magic; 64],
+    attacks: Vec<Bitboard>,
+}
+
+fn sliding_tables() -> &'static SlidingTables {
+    static TABLES: OnceLock<SlidingTables> = OnceLock::new();
+
+    TABLES.get_or_init(|| {
+        let mut tables = SlidingTables {
+            rook: [Magic::default(); 64],
+            bishop: [Magic::default(); 64],
+            attacks: Vec::with_capacity(0x19000 + 0x1480),
+        };
+
+        for square in Square::iter() {
+            let i = square.index();
+
+            tables.rook[i] = fill_magic(square, &ROOK_DIRS, ROOK_MAGICS[i], &mut tables.attacks);
+            tables.bishop[i] =
+                fill_magic(square, &BISHOP_DIRS, BISHOP_MAGICS[i], &mut tables.attacks);
+        }
+
+        tables
+    })
+}
+
+/// Internal helper that returns the squares that can block a slider, a piece on the edge of the
+/// board never blocks anything unless the slider is on that edge
+fn relevant_occupancy(square: Square, dirs: &[(i32, i32)]) -> u64 {
+    let edges = ((Bitboard::from_rank(Rank::FIRST) | Bitboard::from_rank(Rank::EIGHTH))
+        - Bitboard::from_rank(square.rank()))
+        | ((Bitboard::from_file(File::A) | Bitboard::from_file(File::H))
+            - Bitboard::from_file(square.file()));
+
+    sliding_attacks(square, 0, dirs) & !edges.0
+}
+
+/// Internal helper that calls `f` with every subset of a mask, starting with the empty set
+fn for_each_subset(mask: u64, mut f: impl FnMut(u64)) {
+    let mut subset: u64 = 0;
+
+    loop {
+        f(subset);
+
+        subset = subset.wrapping_sub(mask) & mask;
+        if subset == 0 {
+            break;
+        }
+    }
+}
+
+/// Internal helper that adds the attacks of a square to `attacks` for every relevant occupancy
+fn fill_magic(
+    square: Square,
+    dirs: &[(i32, i32)],
+    magic: u64,
+    attacks: &mut Vec<Bitboard>,
+) -> Magic {
+    let mask = relevant_occupancy(square, dirs);
+    let bits = mask.count_ones();
+    let magic = Magic {
+        mask,
+        magic,
+        shift: 64 - bits,
+        offset: attacks.len(),
+    };
+
+    attacks.resize(attacks.len() + (1 << bits), Bitboard::EMPTY);
+
+    for_each_subset(mask, |occupied| {
+        attacks[magic.index(Bitboard(occupied))] =
+            Bitboard(sliding_attacks(square, occupied, dirs));
+    });
+
+    magic
+}
+
+// The magics were found with a random search for sparse numbers, the tests check that each of them
+// maps every occupancy to the right attacks
+#[rustfmt::skip]
+const ROOK_MAGICS: [u64; 64] = [
+    0x1080_0040_0880_1020, 0x0840_0920_02c0_3000, 0x1900_2000_1040_0900, 0x0880_1000_0800_0480,
+    0x4200_1004_2008_0200, 0x8100_0201_0008_0400, 0x0200_0401_1088_6200, 0x0200_0080_4022_0411,
+    0x0404_8000_8440_0220, 0x0000_4010_0040_2000, 0x0086_0010_8122_0440, 0x0408_8008_0010_0280,
+    0x000a_0012_0104_0820, 0x8848_8002_0084_0080, 0x4001_0001_0004_0200, 0x0442_0001_0210_5084,
+    0x9080_0100_2080_4100, 0x0040_4040_0020_1009, 0x0000_8080_1000_2009, 0x2200_0900_21d0_0100,
+    0x0008_0080_0804_0080, 0x0004_0040_0201_0040, 0x0011_0400_0801_5042, 0x0000_0a00_0176_8104,
+    0x0000_8000_8020_4009, 0x2010_0041_4000_2001, 0x9800_2002_8010_0080, 0x1000_1000_8008_0080,
+    0x0050_5005_0008_0100, 0x0000_0200_8004_0080, 0x0c10_0104_0042_0810, 0x1040_0082_0000_5104,
+    0x0180_8240_0880_04a0, 0x0882_8040_0480_2000, 0x0880_4020_0100_1100, 0x2000_2104_0900_1000,
+    0x2000_4801_3100_1500, 0x0000_8004_0080_0200, 0x0000_0238_0c00_1003, 0x4600_0848_8200_0431,
+    0x0080_0020_0050_4000, 0x0300_5000_2000_4002, 0x0040_4082_0022_0011, 0x0010_0400_0800_4040,
+    0x0000_0800_0400_8080, 0x0010_0400_0200_8080, 0x2012_0048_8102_0004, 0x8300_8424_4482_0011,
+    0x0088_4038_8201_0200, 0x0820_4000_8021_0100, 0x0110_9100_40a0_0300, 0x0801_1002_8008_0480,
+    0x0242_0090_0820_0600, 0x1002_0004_8950_0200, 0x0040_8002_0001_0080, 0x0091_8000_4100_0080,
+    0x0000_2093_0048_8001, 0x04c1_0024_1482_4001, 0x0200_2000_0b00_1041, 0x7000_1000_0420_0901,
+    0x8002_0020_0410_0802, 0x3001_0002_084c_0007, 0x0888_2218_0081_3004, 0x4000_0028_4084_0112,
+];
+#[rustfmt::skip]
+const BISHOP_MAGICS: [u64; 64] = [
+    0x20c0_0909_0106_1081, 0x0024_0400_9403_0104, 0x8210_8102_0029_0200, 0x0011_0404_8462_0000,
+    0x0081_1040_0222_1000, 0x0009_0120_1100_1350, 0x0081_0108_0240_0380, 0x0000_4202_1001_0408,
+    0x0008_1050_0228_0050, 0x0001_0284_8404_0044, 0x2a00_8808_1040_8804, 0x7020_0222_8200_0100,
+    0x0084_0404_2010_0a50, 0x0004_0101_0840_e000, 0x2020_0202_1042_0888, 0x0008_0842_0201_2010,
+    0x2010_4008_1001_8800, 0x0445_1220_0802_0840, 0x0804_1008_0800_2008, 0x0008_0021_0411_0100,
+    0x0061_0058_2008_0800, 0x2001_0002_0082_0100, 0x480c_2100_8401_0800, 0x3004_4425_0048_0420,
+    0x1010_1022_4004_8100, 0x0018_2009_0842_20a3, 0x8803_090a_1000_4205, 0x0208_0800_4020_2020,
+    0x000c_0440_8401_0040, 0x00a1_0100_0200_4106, 0x6008_2100_2064_0202, 0x1600_9021_1286_0801,
+    0x0004_2008_c122_0200, 0x010c_0420_0244_0140, 0x5022_0802_0004_0820, 0x0402_0040_4294_0100,
+    0x0860_1084_0000_8020, 0x000c_0800_2202_1000, 0x0264_0806_5282_2100, 0x4005_0312_2101_0401,
+    0x0004_5024_1000_8400, 0x0005_00b0_10a2_0400, 0x0415_0940_5008_0800, 0x0800_0020_1800_a104,
+    0x4022_a803_0400_0110, 0x4012_1408_0202_8020, 0x4020_0104_0101_00a0, 0x1281_0806_008b_0c41,
+    0x0020_4410_0808_0000, 0x2002_1200_8404_5420, 0x0704_0200_6208_0002, 0x0000_0010_8404_0001,
+    0x0322_2008_9124_0200, 0xf040_2002_1002_4800, 0x0140_8248_3200_8042, 0x0002_1002_0a00_4602,
+    0x0083_0428_0514_1020, 0x002c_1200_9a01_1000, 0x0041_a000_4414_0400, 0x0000_4004_020a_0202,
+    0x0000_1400_1002_0210, 0x2864_1608_1101_2200, 0x2060_0808_4108_2a17, 0xa010_0411_0800_3100,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sliding_attacks_should_match_the_rays() {
+        // Pieces on the corners are outside of every mask unless the slider is on the edge
+        let corners = 0x8100_0000_0000_0081;
+
+        for square in Square::iter() {
+            for (dirs, attacks) in [
+                (&ROOK_DIRS, rook_attacks as fn(Square, Bitboard) -> Bitboard),
+                (&BISHOP_DIRS, bishop_attacks),
+            ] {
+                for_each_subset(relevant_occupancy(square, dirs), |occupied| {
+                    let occupied = (occupied | corners) & !(1 << square.index());
+
+                    assert_eq!(
+                        attacks(square, Bitboard(occupied)).0,
+                        sliding_attacks(square, occupied, dirs)
+                    );
+                });
+            }
+        }
+    }
+
+    #[test]
+    fn leaper_tables_should_stay_on_the_board() {
+        assert_eq!(king_attacks(Square::H8).count(), 3);
+        assert_eq!(knight_attacks(Square::D4).count(), 8);
+        assert_eq!(
+            pawn_attacks(Color::White, Square::A2),
+            Bitboard::from(Square::B3)
+        );
+        assert_eq!(
+            pawn_attacks(Color::Black, Square::E7),
+            Bitboard::from_iter([Square::D6, Square::F6])
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, Shr, Sub, SubAssign,
+};
+
+use crate::{File, Rank, Square};
+
+/// A set of squares stored as the bits of a `u64`
+///
+/// Bit `n` is the square with index `n`, so bit 0 is `a1`, bit 7 is `h1` and bit 63 is `h8`. The
+/// set operations are the bitwise operators, `|` is the union, `&` the intersection, `^` the
+/// symmetric difference, `-` the difference and `!` the complement. Iterating over a bitboard
+/// yields its squares from `a1` to `h8`.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{Bitboard, File, Rank, Square};
+///
+/// let file = Bitboard::from_file(File::E);
+/// let rank = Bitboard::from_rank(Rank::FOURTH);
+///
+/// assert_eq!((file & rank).into_iter().collect::<Vec<Square>>(), vec![Square::E4]);
+/// assert_eq!((file | rank).count(), 15);
+/// assert_eq!(Bitboard::from(Square::E4).north(), Bitboard::from(Square::E5));
+/// assert!(Bitboard::from(Square::H4).east().is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    /// A bitboard without any squares
+    pub const EMPTY: Bitboard = Bitboard(0);
+    /// A bitboard with every square
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+
+    /// Returns a bitboard with every square on a file
+    pub const fn from_file(file: File) -> Bitboard {
+        Bitboard(0x0101_0101_0101_0101 << file.index())
+    }
+
+    /// Returns a bitboard with every square on a rank
+    pub const fn from_rank(rank: Rank) -> Bitboard {
+        Bitboard(0xff << (rank.index() * 8))
+    }
+
+    /// Returns a bitboard with a single square
+    pub const fn from_square(square: Square) -> Bitboard {
+        Bitboard(1 << square.index())
+    }
+
+    /// Returns if the bitboard has a square
+    pub const fn contains(self, square: Square) -> bool {
+        self.0 & (1 << square.index()) != 0
+    }
+
+    /// Adds a square to the bitboard
+    pub fn insert(&mut self, square: Square) {
+        self.0 |= 1 << square.index();
+    }
+
+    /// Removes a square from the bitboard
+    pub fn remove(&mut self, square: Square) {
+        self.0 &= !(1 << square.index());
+    }
+
+    /// Returns if the bitboard doesn't have any squares
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the amount of squares in the bitboard
+    pub const fn count(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns the square with the lowest index, the first square when iterating
+    pub const fn first(self) -> Option<Square> {
+        Square::from_index(self.0.trailing_zeros() as usize)
+    }
+
+    /// Removes and returns the square with the lowest index
+    pub fn pop_first(&mut self) -> Option<Square> {
+        let square = self.first()?;
+        self.0 &= self.0 - 1;
+
+        Some(square)
+    }
+
+    /// Moves every square one rank towards the eighth rank, squares on the eighth rank are lost
+    pub const fn north(self) -> Bitboard {
+        Bitboard(self.0 << 8)
+    }
+
+    /// Moves every square one rank towards the first rank, squares on the first rank are lost
+    pub const fn south(self) -> Bitboard {
+        Bitboard(self.0 >> 8)
+    }
+
+    /// Moves every square one file towards the `h` file, squares on the `h` file are lost
+    pub const fn east(self) -> Bitboard {
+        Bitboard((self.0 & !Bitboard::from_file(File::H).0) << 1)
+    }
+
+    /// Moves every square one file towards the `a` file, squares on the `a` file are lost
+    pub const fn west(self) -> Bitboard {
+        Bitboard((self.0 & !Bitboard::from_file(File::A).0) >> 1)
+    }
+}
+
+impl From<Square> for Bitboard {
+    fn from(square: Square) -> Self {
+        Bitboard::from_square(square)
+    }
+}
+
+impl FromIterator<Square> for Bitboard {
+    fn from_iter<T: IntoIterator<Item = Square>>(iter: T) -> Self {
+        let mut bitboard = Bitboard::EMPTY;
+
+        for square in iter {
+            bitboard.insert(square);
+        }
+
+        bitboard
+    }
+}
+
+impl Iterator for Bitboard {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pop_first()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count(), Some(self.count()))
+    }
+}
+
+impl ExactSizeIterator for Bitboard {}
+
+/// Internal helper that implements a binary operator and its assigning version for bitboards
+macro_rules! bitboard_op {
+    ($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, |$a:ident, $b:ident| $op:expr) => {
+        impl $trait for Bitboard {
+            type Output = Bitboard;
+
+            fn $fn(self, rhs: Bitboard) -> Bitboard {
+                let ($a, $b) = (self.0, rhs.0);
+                Bitboard($op)
+            }
+        }
+
+        impl $assign_trait for Bitboard {
+            fn $assign_fn(&mut self, rhs: Bitboard) {
+                *self = $trait::$fn(*self, rhs);
+            }
+        }
+    };
+}
+
+bitboard_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| a & b);
+bitboard_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| a | b);
+bitboard_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| a ^ b);
+bitboard_op!(Sub, sub, SubAssign, sub_assign, |a, b| a & !b);
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
+
+impl Shl<u32> for Bitboard {
+    type Output = Bitboard;
+
+    /// Shifts the bits towards `h8`, squares that are shifted past `h8` are lost
+    fn shl(self, rhs: u32) -> Bitboard {
+        Bitboard(self.0.checked_shl(rhs).unwrap_or(0))
+    }
+}
+
+impl Shr<u32> for Bitboard {
+    type Output = Bitboard;
+
+    /// Shifts the bits towards `a1`, squares that are shifted past `a1` are lost
+    fn shr(self, rhs: u32) -> Bitboard {
+        Bitboard(self.0.checked_shr(rhs).unwrap_or(0))
+    }
+}
+
+impl std::fmt::Display for Bitboard {
+    /// Writes the bitboard as a grid with the eighth rank first, `x` for squares in the set
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for rank in Rank::iter().rev() {
+            let row = File::iter()
+                .map(|file| {
+                    if self.contains(Square::new(file, rank)) {
+                        'x'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>();
+
+            writeln!(f, "{}", row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_operations_should_work() {
+        let a = Bitboard::from_iter([Square::A1, Square::B2, Square::C3]);
+        let b = Bitboard::from_iter([Square::C3, Square::D4]);
+
+        assert_eq!((a | b).count(), 4);
+        assert_eq!(a & b, Bitboard::from(Square::C3));
+        assert_eq!(
+            a ^ b,
+            Bitboard::from_iter([Square::A1, Square::B2, Square::D4])
+        );
+        assert_eq!(a - b, Bitboard::from_iter([Square::A1, Square::B2]));
+        assert_eq!((!a).count(), 61);
+        assert_eq!(
+            a.collect::<Vec<Square>>(),
+            vec![Square::A1, Square::B2, Square::C3]
+        );
+    }
+
+    #[test]
+    fn shifts_should_not_wrap() {
+        let a_file = Bitboard::from_file(File::A);
+        let h_file = Bitboard::from_file(File::H);
+
+        assert_eq!(a_file.west(), Bitboard::EMPTY);
+        assert_eq!(h_file.east(), Bitboard::EMPTY);
+        assert_eq!(a_file.east(), Bitboard::from_file(File::B));
+        assert_eq!(Bitboard::from_rank(Rank::EIGHTH).north(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::from_rank(Rank::FIRST).south(), Bitboard::EMPTY);
+        assert_eq!(Bitboard::FULL << 64, Bitboard::EMPTY);
+        assert_eq!(Bitboard(1) << 63, Bitboard::from(Square::H8));
+    }
+}
@@ -1,12 +1,38 @@
-use crate::{error::FromFenError, Color, Piece, PieceType};
+use crate::{
+    attacks,
+    error::{CoordinateError, FromFenError},
+    Bitboard, Color, Piece, PieceType, Square,
+};
+
+/// Every piece type, in the order they're stored in `Board::piece_types`
+const PIECE_TYPES: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
 
 /// A chess board
-#[derive(Debug, Copy, Clone)]
+///
+/// The pieces are stored as bitboards, one with the squares of each color and one with the
+/// squares of each piece type. The bitboards can be read with `by_color`, `by_piece_type` and
+/// `by_piece`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
 pub struct Board {
-    pub(crate) tiles: [Option<Piece>; 64],
+    /// The squares of the white pieces and the black pieces
+    colors: [Bitboard; 2],
+    /// The squares of the pieces of each type, in the order of `PIECE_TYPES`
+    piece_types: [Bitboard; 6],
 }
 
 impl Board {
+    /// Returns a board without any pieces
+    pub fn empty() -> Board {
+        Board::default()
+    }
+
     /// Parses the board part of a FEN string
     ///
     /// # Arguments
@@ -20,83 +46,291 @@ impl Board {
     /// Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR");
     /// ```
     pub fn from_fen(fen: &str) -> Result<Board, FromFenError> {
-        let mut tiles: [Option<Piece>; 64] = [None; 64];
-
-        let rows = fen.split('/').collect::<Vec<&str>>();
-
-        if rows.len() != 8 {
-            return Err(FromFenError::IncorrectAmountOfSlash);
-        }
-
-        let mut i = 0;
-        for (row_index, row) in rows.iter().enumerate() {
-            for c in row.chars() {
-                let parsed_value = c.to_string().parse::<usize>();
+        Board::parse_fen(fen, 0)
+    }
 
-                if i >= row_index * 8 + 8 {
-                    return Err(FromFenError::IncorrectAmountOfTiles);
+    /// Internal helper that parses the board part of a FEN string
+    ///
+    /// # Arguments
+    /// * `fen` - The board part of a FEN string
+    /// * `start` - The character offset of the board part in the whole FEN string, used in errors
+    pub(crate) fn parse_fen(fen: &str, start: usize) -> Result<Board, FromFenError> {
+        let mut board = Board::empty();
+
+        let mut row = 0;
+        // The amount of tiles in the current row that have been filled in
+        let mut column = 0;
+
+        for (i, c) in fen.chars().enumerate() {
+            let offset = start + i;
+
+            match c {
+                '/' => {
+                    if column != 8 {
+                        return Err(FromFenError::IncorrectAmountOfTiles { field: 0, offset });
+                    }
+                    if row == 7 {
+                        return Err(FromFenError::IncorrectAmountOfSlash { field: 0, offset });
+                    }
+
+                    row += 1;
+                    column = 0;
                 }
+                '1'..='8' => {
+                    column += c as usize - '0' as usize;
 
-                if let Ok(n) = parsed_value {
-                    i += n;
-                } else {
-                    let piece = Piece::try_from(c).map_err(|_| FromFenError::UnknownCharacter)?;
+                    if column > 8 {
+                        return Err(FromFenError::IncorrectAmountOfTiles { field: 0, offset });
+                    }
+                }
+                _ => {
+                    let piece = Piece::try_from(c)
+                        .map_err(|_| FromFenError::UnknownCharacter { field: 0, offset })?;
 
-                    tiles[i] = Some(piece);
+                    if column == 8 {
+                        return Err(FromFenError::IncorrectAmountOfTiles { field: 0, offset });
+                    }
 
-                    i += 1;
+                    let square = Square::from_coords(column, row).expect("Checked above");
+                    board.put(square, Some(piece));
+                    column += 1;
                 }
             }
         }
 
-        if i != 64 {
-            return Err(FromFenError::IncorrectAmountOfTiles);
+        let offset = start + fen.chars().count();
+
+        if row != 7 {
+            return Err(FromFenError::IncorrectAmountOfSlash { field: 0, offset });
+        }
+        if column != 8 {
+            return Err(FromFenError::IncorrectAmountOfTiles { field: 0, offset });
         }
 
-        Ok(Board { tiles })
+        Ok(board)
     }
 
     /// Returns the position of the king of a color
     pub fn get_king_pos(&self, color: Color) -> Option<(usize, usize)> {
-        (0..64).map(|i| (i % 8, i / 8)).find(|(x, y)| {
-            if let Some(piece) = self.get_tile(*x, *y) {
-                return piece.color == color && piece.piece_type == PieceType::King;
-            }
-            false
-        })
+        self.by_piece(color, PieceType::King)
+            .next()
+            .map(|square| square.coords())
     }
 
     /// Returns a piece on the board
+    ///
+    /// # Panics
+    /// If x or y is outside the board, see `try_get_tile` for a version that doesn't panic
     pub fn get_tile(&self, x: usize, y: usize) -> Option<Piece> {
-        if x > 7 || y > 7 {
-            panic!("x and y must be between 0 and 7");
-        }
-
-        let index = y * 8 + x;
-
-        self.tiles[index]
+        self.try_get_tile(x, y)
+            .expect("x and y must be between 0 and 7")
     }
 
     /// Sets a tile on the board
+    ///
+    /// # Panics
+    /// If x or y is outside the board, see `try_set_tile` for a version that doesn't panic
     pub fn set_tile(&mut self, x: usize, y: usize, piece: Piece) {
-        if x > 7 || y > 7 {
-            panic!("x and y must be between 0 and 7");
-        }
+        self.try_set_tile(x, y, piece)
+            .expect("x and y must be between 0 and 7")
+    }
+
+    /// Removes a tile from the board
+    ///
+    /// # Panics
+    /// If x or y is outside the board, see `try_remove_tile` for a version that doesn't panic
+    pub fn remove_tile(&mut self, x: usize, y: usize) {
+        self.try_remove_tile(x, y)
+            .expect("x and y must be between 0 and 7");
+    }
 
-        let index = y * 8 + x;
+    /// Returns a piece on the board
+    ///
+    /// # Returns
+    /// * `Result<Option<Piece>, CoordinateError>` - The piece on the tile, or an error if x or y
+    ///   is outside the board
+    pub fn try_get_tile(&self, x: usize, y: usize) -> Result<Option<Piece>, CoordinateError> {
+        Ok(self.piece_at(Square::try_from((x, y))?))
+    }
 
-        self.tiles[index] = Some(piece);
+    /// Sets a tile on the board
+    ///
+    /// # Returns
+    /// * `Result<(), CoordinateError>` - An error if x or y is outside the board
+    pub fn try_set_tile(
+        &mut self,
+        x: usize,
+        y: usize,
+        piece: Piece,
+    ) -> Result<(), CoordinateError> {
+        self.set_piece(Square::try_from((x, y))?, piece);
+
+        Ok(())
     }
 
     /// Removes a tile from the board
-    pub fn remove_tile(&mut self, x: usize, y: usize) {
-        if x > 7 || y > 7 {
-            panic!("x and y must be between 0 and 7");
+    ///
+    /// # Returns
+    /// * `Result<Option<Piece>, CoordinateError>` - The piece that was removed, or an error if x
+    ///   or y is outside the board
+    pub fn try_remove_tile(
+        &mut self,
+        x: usize,
+        y: usize,
+    ) -> Result<Option<Piece>, CoordinateError> {
+        Ok(self.remove_piece(Square::try_from((x, y))?))
+    }
+
+    /// Places a piece on a square, replacing the piece that was there
+    pub fn set_piece(&mut self, square: Square, piece: Piece) {
+        self.put(square, Some(piece));
+    }
+
+    /// Removes the piece on a square
+    ///
+    /// # Returns
+    /// * `Option<Piece>` - The piece that was removed, if the square wasn't empty
+    pub fn remove_piece(&mut self, square: Square) -> Option<Piece> {
+        self.put(square, None)
+    }
+
+    /// Returns the piece on a square
+    pub fn piece_at(&self, square: Square) -> Option<Piece> {
+        let color = if self.colors[0].contains(square) {
+            Color::White
+        } else if self.colors[1].contains(square) {
+            Color::Black
+        } else {
+            return None;
+        };
+
+        let piece_type = PIECE_TYPES
+            .into_iter()
+            .find(|piece_type| self.piece_types[*piece_type as usize].contains(square))
+            .expect("A square with a color has a piece type");
+
+        Some(Piece { piece_type, color })
+    }
+
+    /// Internal helper that sets or clears a square
+    ///
+    /// # Returns
+    /// * `Option<Piece>` - The piece that was on the square before
+    fn put(&mut self, square: Square, piece: Option<Piece>) -> Option<Piece> {
+        let previous = self.piece_at(square);
+
+        if let Some(previous) = previous {
+            self.colors[previous.color as usize].remove(square);
+            self.piece_types[previous.piece_type as usize].remove(square);
         }
 
-        let index = y * 8 + x;
+        if let Some(piece) = piece {
+            self.colors[piece.color as usize].insert(square);
+            self.piece_types[piece.piece_type as usize].insert(square);
+        }
+
+        previous
+    }
 
-        self.tiles[index] = None;
+    /// Returns the squares of every piece on the board
+    pub fn occupied(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
+    }
+
+    /// Returns the squares of the pieces of a color
+    pub fn by_color(&self, color: Color) -> Bitboard {
+        self.colors[color as usize]
+    }
+
+    /// Returns the squares of the pieces of a type, of both colors
+    pub fn by_piece_type(&self, piece_type: PieceType) -> Bitboard {
+        self.piece_types[piece_type as usize]
+    }
+
+    /// Returns the squares of the pieces of a color and type, from `a1` to `h8`
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Board, Color, PieceType, Square};
+    ///
+    /// let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR").unwrap();
+    ///
+    /// let knights = board.by_piece(Color::White, PieceType::Knight).collect::<Vec<Square>>();
+    /// assert_eq!(knights, vec![Square::B1, Square::G1]);
+    /// ```
+    pub fn by_piece(&self, color: Color, piece_type: PieceType) -> Bitboard {
+        self.by_color(color) & self.by_piece_type(piece_type)
+    }
+
+    /// Returns the squares of the pieces of a color that attack a square
+    ///
+    /// A piece attacks a square if it could capture a piece of the other color on it, so pawns
+    /// attack diagonally forwards and pinned pieces still attack. The piece on the square itself
+    /// doesn't matter.
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Board, Color, Square};
+    ///
+    /// let board = Board::from_fen("4k3/8/8/3p4/8/5N2/8/4R1K1").unwrap();
+    ///
+    /// let attackers = board.attackers(Square::E5, Color::White).collect::<Vec<Square>>();
+    /// assert_eq!(attackers, vec![Square::E1, Square::F3]);
+    /// assert!(board.is_attacked(Square::E4, Color::Black));
+    /// ```
+    pub fn attackers(&self, square: Square, color: Color) -> Bitboard {
+        self.attackers_through(square, color, self.occupied())
+    }
+
+    /// Returns if any piece of a color attacks a square, see `attackers`
+    pub fn is_attacked(&self, square: Square, color: Color) -> bool {
+        !self.attackers(square, color).is_empty()
+    }
+
+    /// Returns the pieces of a color that attack a square as if `occupied` were the pieces on the
+    /// board, used to see through pieces that are about to move
+    pub(crate) fn attackers_through(
+        &self,
+        square: Square,
+        color: Color,
+        occupied: Bitboard,
+    ) -> Bitboard {
+        let diagonal = self.by_piece_type(PieceType::Bishop) | self.by_piece_type(PieceType::Queen);
+        let straight = self.by_piece_type(PieceType::Rook) | self.by_piece_type(PieceType::Queen);
+
+        // A piece attacks the square if the same piece on the square would attack it
+        let attackers = (attacks::pawn_attacks(color.opposite(), square)
+            & self.by_piece_type(PieceType::Pawn))
+            | (attacks::knight_attacks(square) & self.by_piece_type(PieceType::Knight))
+            | (attacks::king_attacks(square) & self.by_piece_type(PieceType::King))
+            | (attacks::bishop_attacks(square, occupied) & diagonal)
+            | (attacks::rook_attacks(square, occupied) & straight);
+
+        attackers & self.by_color(color)
+    }
+
+    /// Returns all pieces on the board together with their squares, from `a1` to `h8`
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Board, Color};
+    ///
+    /// let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2R").unwrap();
+    ///
+    /// let white = board.iter().filter(|(_, piece)| piece.color == Color::White).count();
+    /// assert_eq!(white, 2);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (Square, Piece)> + '_ {
+        self.occupied().map(|square| {
+            let piece = self.piece_at(square).expect("The square is occupied");
+
+            (square, piece)
+        })
+    }
+
+    /// Internal helper that returns the piece on every tile, row by row from `a8` to `h1`
+    fn tiles(&self) -> impl Iterator<Item = Option<Piece>> + '_ {
+        (0..64).map(|i| self.get_tile(i % 8, i / 8))
     }
 
     /// Returns the board as a FEN string
@@ -105,7 +339,7 @@ impl Board {
 
         let mut empty_tiles = 0;
 
-        for (i, tile) in self.tiles.into_iter().enumerate() {
+        for (i, tile) in self.tiles().enumerate() {
             if i % 8 == 0 && i != 0 {
                 if empty_tiles != 0 {
                     fen.push_str(&empty_tiles.to_string());
@@ -137,27 +371,17 @@ impl Board {
     }
 }
 
-impl Eq for Board {}
-impl PartialEq for Board {
-    fn eq(&self, other: &Self) -> bool {
-        self.tiles
-            .into_iter()
-            .zip(other.tiles.into_iter())
-            .all(|(a, b)| a == b)
-    }
-}
-
 impl std::fmt::Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut game_string = String::new();
 
-        for (i, tile) in self.tiles.iter().enumerate() {
+        for (i, tile) in self.tiles().enumerate() {
             if i % 8 == 0 && i != 0 {
-                game_string.push_str("\n");
+                game_string.push('\n');
             }
 
             if let Some(piece) = tile {
-                let piece_char: char = (*piece).into();
+                let piece_char: char = piece.into();
 
                 game_string.push(piece_char);
             } else {
@@ -198,4 +422,65 @@ mod tests {
         assert_eq!(Some((4, 3)), board.get_king_pos(Color::Black));
         assert_eq!(Some((2, 6)), board.get_king_pos(Color::White));
     }
+
+    #[test]
+    pub fn try_accessors_should_not_panic() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3").unwrap();
+        let rook = Piece {
+            piece_type: PieceType::Rook,
+            color: Color::White,
+        };
+
+        assert!(matches!(
+            board.try_get_tile(8, 0),
+            Err(CoordinateError::OutOfBounds { x: 8, y: 0 })
+        ));
+        assert!(board.try_set_tile(0, usize::MAX, rook).is_err());
+        assert!(board.try_remove_tile(9, 9).is_err());
+
+        board.try_set_tile(7, 7, rook).unwrap();
+        assert_eq!(board.try_get_tile(7, 7).unwrap(), Some(rook));
+        assert_eq!(
+            board.by_piece(Color::White, PieceType::Rook).next(),
+            Some(Square::H1)
+        );
+        assert_eq!(board.iter().count(), 3);
+        assert_eq!(board.try_remove_tile(7, 7).unwrap(), Some(rook));
+        assert_eq!(board.fen(), "4k3/8/8/8/8/8/8/4K3");
+    }
+
+    #[test]
+    pub fn piece_at_should_match_get_tile() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR").unwrap();
+
+        for square in Square::iter() {
+            let (x, y) = square.coords();
+            assert_eq!(board.piece_at(square), board.get_tile(x, y));
+        }
+
+        assert_eq!(
+            board.piece_at(Square::E1),
+            Some(Piece {
+                piece_type: PieceType::King,
+                color: Color::White
+            })
+        );
+    }
+
+    #[test]
+    pub fn set_piece_should_replace_the_piece_on_the_square() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K3").unwrap();
+        let knight = Piece {
+            piece_type: PieceType::Knight,
+            color: Color::Black,
+        };
+
+        board.set_piece(Square::E1, knight);
+        assert_eq!(board.piece_at(Square::E1), Some(knight));
+        assert!(board.by_piece(Color::White, PieceType::King).is_empty());
+
+        assert_eq!(board.remove_piece(Square::E1), Some(knight));
+        assert_eq!(board.remove_piece(Square::E1), None);
+        assert_eq!(board.fen(), "4k3/8/8/8/8/8/8/8");
+    }
 }
@@ -1,21 +1,87 @@
+use crate::{Color, Square};
+
+/// An error from parsing a FEN string
+///
+/// Every variant holds the index of the FEN field with the problem, 0 is the piece placement and
+/// 5 is the fullmove number, and the character offset of the problem in the whole string.
 #[derive(thiserror::Error, Debug)]
 pub enum FromFenError {
-    #[error("FEN string has too many or too few slashes")]
-    IncorrectAmountOfSlash,
-    #[error("Unknown character in fen string")]
-    UnknownCharacter,
-    #[error("FEN string has too many or too few tiles")]
-    IncorrectAmountOfTiles,
-    #[error("FEN string has too many or too few parts")]
-    IncorrectAmountOfParts,
-    #[error("Unknown turn")]
-    UnknownTurn,
-    #[error("Repeating characters in castling part")]
-    RepeatingCharactersInCastlingPart,
-    #[error("Incorrect length")]
-    IncorrectLength,
-    #[error("Invalid en passant")]
-    InvalidEnPassant,
+    #[error("FEN string has too many or too few slashes (field {field}, offset {offset})")]
+    IncorrectAmountOfSlash { field: usize, offset: usize },
+    #[error("Unknown character in fen string (field {field}, offset {offset})")]
+    UnknownCharacter { field: usize, offset: usize },
+    #[error("FEN string has too many or too few tiles (field {field}, offset {offset})")]
+    IncorrectAmountOfTiles { field: usize, offset: usize },
+    #[error("FEN string has too many or too few parts (field {field}, offset {offset})")]
+    IncorrectAmountOfParts { field: usize, offset: usize },
+    #[error("Unknown turn (field {field}, offset {offset})")]
+    UnknownTurn { field: usize, offset: usize },
+    #[error("Repeating characters in castling part (field {field}, offset {offset})")]
+    RepeatingCharactersInCastlingPart { field: usize, offset: usize },
+    #[error("Incorrect length (field {field}, offset {offset})")]
+    IncorrectLength { field: usize, offset: usize },
+    #[error("Invalid en passant (field {field}, offset {offset})")]
+    InvalidEnPassant { field: usize, offset: usize },
+    #[error("Invalid halfmove clock (field {field}, offset {offset})")]
+    InvalidHalfmoveClock { field: usize, offset: usize },
+    #[error("Invalid fullmove number (field {field}, offset {offset})")]
+    InvalidFullmoveNumber { field: usize, offset: usize },
+}
+
+impl FromFenError {
+    /// Returns the index of the FEN field with the problem, 0 is the piece placement
+    pub fn field(&self) -> usize {
+        self.location().0
+    }
+
+    /// Returns the character offset of the problem in the FEN string
+    pub fn offset(&self) -> usize {
+        self.location().1
+    }
+
+    fn location(&self) -> (usize, usize) {
+        match *self {
+            FromFenError::IncorrectAmountOfSlash { field, offset }
+            | FromFenError::UnknownCharacter { field, offset }
+            | FromFenError::IncorrectAmountOfTiles { field, offset }
+            | FromFenError::IncorrectAmountOfParts { field, offset }
+            | FromFenError::UnknownTurn { field, offset }
+            | FromFenError::RepeatingCharactersInCastlingPart { field, offset }
+            | FromFenError::IncorrectLength { field, offset }
+            | FromFenError::InvalidEnPassant { field, offset }
+            | FromFenError::InvalidHalfmoveClock { field, offset }
+            | FromFenError::InvalidFullmoveNumber { field, offset } => (field, offset),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum StrictFromFenError {
+    #[error(transparent)]
+    InvalidFen(#[from] FromFenError),
+    #[error("The position can't be reached in a game: {0:?}")]
+    InvalidPosition(Vec<PositionError>),
+}
+
+/// A reason that a position can't be reached in a game, see `Game::validate`
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum PositionError {
+    #[error("{0:?} has no king")]
+    MissingKing(Color),
+    #[error("{0:?} has more than one king")]
+    TooManyKings(Color),
+    #[error("There is a pawn on the first or last rank at {0}")]
+    PawnOnBackRank(Square),
+    #[error("{0:?} has more than eight pawns")]
+    TooManyPawns(Color),
+    #[error("{0:?} has more promoted pieces than missing pawns")]
+    TooManyPromotedPieces(Color),
+    #[error("The color that isn't to move is in check")]
+    OpponentInCheck,
+    #[error("Castling right {0:?} without the king and rook on their starting tiles")]
+    ImpossibleCastlingRight(char),
+    #[error("The pawn at {0} can't have just made a double pawn push")]
+    ImpossibleEnPassant(Square),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -24,6 +90,10 @@ pub enum GameApplyMoveError {
     InvalidMove,
 }
 
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("The position has more than {} legal moves", crate::MAX_MOVES)]
+pub struct TooManyMovesError;
+
 #[derive(thiserror::Error, Debug)]
 pub enum ParsePieceError {
     #[error("Provided string is too long")]
@@ -33,3 +103,71 @@ pub enum ParsePieceError {
     #[error("Unknown character piece")]
     UnknownCharacterPiece,
 }
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseSanError {
+    #[error("The SAN string is malformed")]
+    Malformed,
+    #[error("The move is not legal in this position")]
+    IllegalMove,
+    #[error("The move matches more than one legal move")]
+    AmbiguousMove,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseUciError {
+    #[error("The UCI move is malformed")]
+    Malformed,
+    #[error("The move is not legal in this position")]
+    IllegalMove,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParsePgnError {
+    #[error("Malformed tag pair near {0:?}")]
+    InvalidTag(String),
+    #[error("Invalid FEN tag: {0}")]
+    InvalidFen(FromFenError),
+    #[error("Invalid move {token:?} at move {move_number}: {source}")]
+    InvalidMove {
+        move_number: u32,
+        token: String,
+        source: ParseSanError,
+    },
+    #[error("Unexpected token {token:?} at move {move_number}")]
+    UnexpectedToken { move_number: u32, token: String },
+    #[error("Unexpected character {0:?}")]
+    UnexpectedCharacter(char),
+    #[error("Unknown result {0:?}")]
+    UnknownResult(String),
+    #[error("Comment is never closed")]
+    UnterminatedComment,
+    #[error("String is never closed")]
+    UnterminatedString,
+    #[error("Variation is never closed")]
+    UnterminatedVariation,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReadPgnError {
+    #[error("Failed to read PGN: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Malformed game starting on line {line}: {source}")]
+    MalformedGame { line: usize, source: ParsePgnError },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseSquareError {
+    #[error("Expected a square like \"e4\", got {0:?}")]
+    Malformed(String),
+    #[error("Unknown file {0:?}")]
+    InvalidFile(char),
+    #[error("Unknown rank {0:?}")]
+    InvalidRank(char),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CoordinateError {
+    #[error("({x}, {y}) is outside the board, x and y must be between 0 and 7")]
+    OutOfBounds { x: usize, y: usize },
+}
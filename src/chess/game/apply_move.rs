@@ -1,14 +1,17 @@
-use crate::{error::GameApplyMoveError, Color, Game, Move, Piece, PieceType};
+use crate::{error::GameApplyMoveError, Color, Game, HistoryEntry, Move, Piece, PieceType};
 
 impl Game {
     /// Applies a move to the game
     ///
+    /// The move is recorded in the game history so that it can be undone with `undo_move`.
+    /// Applying a move clears any moves that could have been redone with `redo_move`.
+    ///
     /// # Arguments
     /// * `mv` - The move to apply
     ///
     /// # Returns
     /// * `Result<(), Box<dyn Error>>` - A result that holds nothing if the move was applied
-    /// successfully or an error if the move was invalid
+    ///   successfully or an error if the move was invalid
     ///
     /// # Examples
     /// ```
@@ -19,25 +22,67 @@ impl Game {
     /// game.apply_move(Move::Quiet { from: (4, 6), to: (4, 5) });
     /// ```
     pub fn apply_move(&mut self, mv: Move) -> Result<(), GameApplyMoveError> {
-        self.en_passant = None;
+        let position_key = self.position_key();
+        let entry = self.play_move(mv)?;
 
-        if mv.is_capture() {
-            let (c_x, c_y) = mv.capture().expect("This is a capture move");
+        self.history.push(entry);
+        self.position_keys.push(position_key);
+        self.redo.clear();
 
-            self.board.remove_tile(c_x, c_y);
-            remove_castling_rights_pos(self, (c_x, c_y));
-        }
+        Ok(())
+    }
+
+    /// Applies a move to the game without recording it in the history
+    ///
+    /// # Returns
+    /// * `Result<HistoryEntry, GameApplyMoveError>` - The information needed to take the move
+    ///   back, or an error if the move was invalid
+    pub(crate) fn play_move(&mut self, mv: Move) -> Result<HistoryEntry, GameApplyMoveError> {
+        let mut entry = HistoryEntry {
+            mv,
+            captured: None,
+            castling: [
+                self.white_kingside_castle,
+                self.white_queenside_castle,
+                self.black_kingside_castle,
+                self.black_queenside_castle,
+            ],
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+        };
 
         let (to_x, to_y) = mv.to();
         let (from_x, from_y) = mv.from();
 
+        // The move is checked before anything changes, so a rejected move leaves the game as it was
         let piece = self
             .board
             .get_tile(from_x, from_y)
             .ok_or(GameApplyMoveError::InvalidMove)?;
+        let rook = match mv {
+            Move::Castle { rook_from, .. } => Some(
+                self.board
+                    .get_tile(rook_from.0, rook_from.1)
+                    .ok_or(GameApplyMoveError::InvalidMove)?,
+            ),
+            _ => None,
+        };
 
-        self.board.remove_tile(from_x, from_y);
-        self.board.set_tile(to_x, to_y, piece);
+        // The castling rights, en passant and turn are added back to the hash key after the move
+        self.hash ^= self.state_hash();
+        self.en_passant = None;
+
+        if mv.is_capture() {
+            let (c_x, c_y) = mv.capture().expect("This is a capture move");
+
+            entry.captured = self.board.get_tile(c_x, c_y);
+            self.put_piece(c_x, c_y, None);
+            remove_castling_rights_pos(self, (c_x, c_y));
+        }
+
+        self.put_piece(from_x, from_y, None);
+        self.put_piece(to_x, to_y, Some(piece));
 
         // Remove castling rights if the type is king
         if piece.piece_type == PieceType::King {
@@ -56,14 +101,11 @@ impl Game {
             Move::Castle {
                 rook_from, rook_to, ..
             } => {
-                if let Some(rook_piece) = self.board.get_tile(rook_from.0, rook_from.1) {
-                    remove_castling_rights_color(self, rook_piece.color);
-
-                    self.board.remove_tile(rook_from.0, rook_from.1);
-                    self.board.set_tile(rook_to.0, rook_to.1, rook_piece);
-                } else {
-                    return Err(GameApplyMoveError::InvalidMove);
-                }
+                let rook = rook.expect("The rook is checked further up in this function");
+                remove_castling_rights_color(self, rook.color);
+
+                self.put_piece(rook_from.0, rook_from.1, None);
+                self.put_piece(rook_to.0, rook_to.1, Some(rook));
             }
             Move::QuietPromotion { .. } | Move::CapturePromotion { .. } => {
                 let piece = self
@@ -71,21 +113,33 @@ impl Game {
                     .get_tile(to_x, to_y)
                     .expect("The to tile is set further up in this function");
 
-                self.board.set_tile(
+                self.put_piece(
                     to_x,
                     to_y,
-                    Piece {
+                    Some(Piece {
                         piece_type: mv.promotion().expect("This is a promotion move"),
                         color: piece.color,
-                    },
+                    }),
                 );
             }
             _ => (),
         };
 
+        if mv.is_capture() || piece.piece_type == PieceType::Pawn {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock = self.halfmove_clock.saturating_add(1);
+        }
+
+        // The clocks can be set to anything in a FEN string, so they stop at the largest value
+        if self.turn == Color::Black {
+            self.fullmove_number = self.fullmove_number.saturating_add(1);
+        }
+
         self.turn = self.turn.opposite();
+        self.hash ^= self.state_hash();
 
-        Ok(())
+        Ok(entry)
     }
 }
 
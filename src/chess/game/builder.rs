@@ -0,0 +1,254 @@
+use crate::{error::PositionError, Board, Color, Game, Piece, Rank, Square};
+
+/// Sets up a position piece by piece
+///
+/// Unlike `Game::set_board` and `Game::set_turn` the builder keeps the castling rights and en
+/// passant that are set, and the position is validated with `Game::validate` before a game is
+/// returned.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{Color, Piece, PieceType, PositionBuilder, Square};
+///
+/// let king = |color| Piece { piece_type: PieceType::King, color };
+/// let rook = Piece { piece_type: PieceType::Rook, color: Color::White };
+///
+/// let game = PositionBuilder::new()
+///     .set_piece(Square::E1, king(Color::White))
+///     .set_piece(Square::H1, rook)
+///     .set_piece(Square::E8, king(Color::Black))
+///     .set_castling(Color::White, true, false)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(game.fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+/// ```
+#[derive(Debug, Clone)]
+pub struct PositionBuilder {
+    board: Board,
+    turn: Color,
+    /// White kingside, white queenside, black kingside and black queenside
+    castling: [bool; 4],
+    /// The square behind the pawn that can be captured en passant, like in FEN
+    en_passant: Option<Square>,
+    halfmove_clock: u32,
+    fullmove_number: u32,
+}
+
+impl PositionBuilder {
+    /// Creates a builder with an empty board and white to move
+    pub fn new() -> PositionBuilder {
+        PositionBuilder::from_board(Board::empty())
+    }
+
+    /// Creates a builder with the pieces of a board, white to move and no castling rights
+    pub fn from_board(board: Board) -> PositionBuilder {
+        PositionBuilder {
+            board,
+            turn: Color::White,
+            castling: [false; 4],
+            en_passant: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+        }
+    }
+
+    /// Creates a builder with the position of a game, the history of the game isn't kept
+    pub fn from_game(game: &Game) -> PositionBuilder {
+        let en_passant = game.en_passant.and_then(|(x, y)| {
+            let pawn = Square::from_coords(x, y)?;
+            let direction = if game.turn == Color::White { 1 } else { -1 };
+
+            pawn.offset(0, direction)
+        });
+
+        PositionBuilder {
+            board: game.board,
+            turn: game.turn,
+            castling: [
+                game.white_kingside_castle,
+                game.white_queenside_castle,
+                game.black_kingside_castle,
+                game.black_queenside_castle,
+            ],
+            en_passant,
+            halfmove_clock: game.halfmove_clock,
+            fullmove_number: game.fullmove_number,
+        }
+    }
+
+    /// Places a piece on a square, replacing the piece that was there
+    pub fn set_piece(&mut self, square: Square, piece: Piece) -> &mut PositionBuilder {
+        self.board.set_piece(square, piece);
+        self
+    }
+
+    /// Removes the piece on a square
+    pub fn remove_piece(&mut self, square: Square) -> &mut PositionBuilder {
+        self.board.remove_piece(square);
+        self
+    }
+
+    /// Removes every piece from the board
+    pub fn clear(&mut self) -> &mut PositionBuilder {
+        self.board = Board::empty();
+        self
+    }
+
+    /// Sets the color to move
+    pub fn set_turn(&mut self, turn: Color) -> &mut PositionBuilder {
+        self.turn = turn;
+        self
+    }
+
+    /// Sets the castling rights of a color
+    ///
+    /// # Arguments
+    /// * `color` - The color to set the castling rights of
+    /// * `kingside` - If the color can castle kingside
+    /// * `queenside` - If the color can castle queenside
+    pub fn set_castling(
+        &mut self,
+        color: Color,
+        kingside: bool,
+        queenside: bool,
+    ) -> &mut PositionBuilder {
+        let offset = if color == Color::White { 0 } else { 2 };
+
+        self.castling[offset] = kingside;
+        self.castling[offset + 1] = queenside;
+        self
+    }
+
+    /// Sets the en passant square
+    ///
+    /// # Arguments
+    /// * `square` - The square behind the pawn that just made a double pawn push, like in FEN,
+    ///   e.g `e3` after `e4`. None if no pawn can be captured en passant
+    pub fn set_en_passant(&mut self, square: Option<Square>) -> &mut PositionBuilder {
+        self.en_passant = square;
+        self
+    }
+
+    /// Sets the halfmove clock, the amount of half moves since the last capture or pawn move
+    pub fn set_halfmove_clock(&mut self, halfmove_clock: u32) -> &mut PositionBuilder {
+        self.halfmove_clock = halfmove_clock;
+        self
+    }
+
+    /// Sets the fullmove number, it's clamped to at least 1
+    pub fn set_fullmove_number(&mut self, fullmove_number: u32) -> &mut PositionBuilder {
+        self.fullmove_number = fullmove_number.max(1);
+        self
+    }
+
+    /// Returns the board that's being built
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Creates a game from the position
+    ///
+    /// # Returns
+    /// * `Result<Game, Vec<PositionError>>` - The game, or every problem that `Game::validate`
+    ///   found with the position
+    pub fn build(&self) -> Result<Game, Vec<PositionError>> {
+        let en_passant = match self.en_passant {
+            None => None,
+            Some(square) => {
+                let (en_passant_rank, pawn_rank) = match self.turn {
+                    Color::White => (Rank::SIXTH, Rank::FIFTH),
+                    Color::Black => (Rank::THIRD, Rank::FOURTH),
+                };
+
+                if square.rank() != en_passant_rank {
+                    return Err(vec![PositionError::ImpossibleEnPassant(square)]);
+                }
+
+                Some(Square::new(square.file(), pawn_rank).coords())
+            }
+        };
+
+        let mut game = Game {
+            board: self.board,
+            turn: self.turn,
+            en_passant,
+            white_kingside_castle: self.castling[0],
+            white_queenside_castle: self.castling[1],
+            black_kingside_castle: self.castling[2],
+            black_queenside_castle: self.castling[3],
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            hash: 0,
+            history: Vec::new(),
+            position_keys: Vec::new(),
+            redo: Vec::new(),
+        };
+        game.hash = game.compute_hash();
+
+        let errors = game.validate();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(game)
+    }
+}
+
+impl Default for PositionBuilder {
+    fn default() -> Self {
+        PositionBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PieceType;
+
+    #[test]
+    fn should_round_trip_a_game() {
+        let fen = "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3";
+        let game = Game::from_fen(fen).unwrap();
+
+        assert_eq!(
+            PositionBuilder::from_game(&game).build().unwrap().fen(),
+            fen
+        );
+    }
+
+    #[test]
+    fn should_validate_the_position() {
+        let mut builder = PositionBuilder::from_game(&Game::start_pos());
+
+        builder.remove_piece(Square::E1);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            vec![
+                PositionError::MissingKing(Color::White),
+                PositionError::ImpossibleCastlingRight('K'),
+                PositionError::ImpossibleCastlingRight('Q')
+            ]
+        );
+
+        builder
+            .set_piece(
+                Square::E1,
+                Piece {
+                    piece_type: PieceType::King,
+                    color: Color::White,
+                },
+            )
+            .set_en_passant(Some(Square::E6));
+        assert_eq!(
+            builder.build().unwrap_err(),
+            vec![PositionError::ImpossibleEnPassant(Square::E5)]
+        );
+
+        builder.set_en_passant(None).set_turn(Color::Black);
+        assert_eq!(
+            builder.build().unwrap().fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1"
+        );
+    }
+}
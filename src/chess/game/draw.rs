@@ -0,0 +1,203 @@
+use crate::{Color, Game, Piece, PieceType, Square};
+
+impl Game {
+    /// Returns if the current turn can claim a draw by the fifty-move rule
+    ///
+    /// A draw can be claimed when the last fifty moves by each player have been made without any
+    /// capture or pawn move. If the move that completed the fifty moves was checkmate, the
+    /// checkmate takes precedence and no draw can be claimed.
+    ///
+    /// # Returns
+    /// * `bool` - If a draw can be claimed
+    pub fn is_fifty_move_draw_claimable(&self) -> bool {
+        self.halfmove_clock >= 100 && !self.is_checkmate()
+    }
+
+    /// Returns if the game is drawn by the seventy-five-move rule
+    ///
+    /// Unlike the fifty-move rule this draw doesn't have to be claimed, the game is over as soon
+    /// as seventy-five moves by each player have been made without any capture or pawn move. A
+    /// checkmate on the last of those moves still takes precedence.
+    ///
+    /// # Returns
+    /// * `bool` - If the game is drawn
+    pub fn is_seventy_five_move_draw(&self) -> bool {
+        self.halfmove_clock >= 150 && !self.is_checkmate()
+    }
+
+    /// Returns if neither player can ever checkmate the other
+    ///
+    /// This is the case for king against king, king and a single bishop or knight against king,
+    /// and positions where the only pieces besides the kings are bishops on tiles of the same
+    /// color, e.g king and bishop against king and bishop with same colored bishops.
+    ///
+    /// # Returns
+    /// * `bool` - If the position is dead because of insufficient material
+    pub fn is_insufficient_material(&self) -> bool {
+        let pieces = self.non_king_pieces();
+
+        match pieces.as_slice() {
+            [] => true,
+            [(_, piece)] => {
+                piece.piece_type == PieceType::Knight || piece.piece_type == PieceType::Bishop
+            }
+            [(square, _), ..] => pieces.iter().all(|(bishop, piece)| {
+                piece.piece_type == PieceType::Bishop
+                    && square_color(*bishop) == square_color(*square)
+            }),
+        }
+    }
+
+    /// Returns if a color has enough material to possibly checkmate the other color
+    ///
+    /// This is stricter than `is_insufficient_material` since it looks at a single color, which
+    /// is what's needed when adjudicating a timeout. If the player who ran out of time has an
+    /// opponent without mating material the game is a draw. Note that a lone knight can still
+    /// mate if the other color has pieces that can block their own king.
+    ///
+    /// # Arguments
+    /// * `color` - The color to check the material of
+    ///
+    /// # Returns
+    /// * `bool` - If there is any series of legal moves where `color` checkmates
+    pub fn has_mating_material(&self, color: Color) -> bool {
+        let pieces = self.non_king_pieces();
+
+        let (own, other): (Vec<_>, Vec<_>) = pieces.iter().partition(|(_, p)| p.color == color);
+
+        let count = |pieces: &[&(Square, Piece)], piece_type: PieceType| {
+            pieces
+                .iter()
+                .filter(|(_, p)| p.piece_type == piece_type)
+                .count()
+        };
+
+        if own.is_empty() {
+            return false;
+        }
+
+        if count(&own, PieceType::Pawn)
+            + count(&own, PieceType::Rook)
+            + count(&own, PieceType::Queen)
+            > 0
+        {
+            return true;
+        }
+
+        let knights = count(&own, PieceType::Knight);
+
+        if knights > 0 {
+            // A single knight needs something other than a queen to block the other king in
+            return own.len() > 1 || other.iter().any(|(_, p)| p.piece_type != PieceType::Queen);
+        }
+
+        // Only bishops left, they need bishops on both colors or something to block the king
+        let mut bishop_colors = pieces
+            .iter()
+            .filter(|(_, p)| p.piece_type == PieceType::Bishop)
+            .map(|(square, _)| square_color(*square));
+        let first_color = bishop_colors.next();
+
+        bishop_colors.any(|c| Some(c) != first_color)
+            || count(&other, PieceType::Pawn) + count(&other, PieceType::Knight) > 0
+    }
+
+    /// Internal helper that returns all pieces on the board that are not kings
+    fn non_king_pieces(&self) -> Vec<(Square, Piece)> {
+        self.board
+            .iter()
+            .filter(|(_, p)| p.piece_type != PieceType::King)
+            .collect()
+    }
+}
+
+/// Internal helper that returns the color of a square, 0 for dark squares and 1 for light squares
+fn square_color(square: Square) -> usize {
+    (square.file().index() + square.rank().index()) % 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fifty_move_draw_should_need_a_hundred_half_moves() {
+        let game = Game::from_fen("8/8/4k3/8/8/3K4/8/7R w - - 99 80").unwrap();
+        assert!(!game.is_fifty_move_draw_claimable());
+
+        let game = Game::from_fen("8/8/4k3/8/8/3K4/8/7R w - - 100 80").unwrap();
+        assert!(game.is_fifty_move_draw_claimable());
+        assert!(!game.is_seventy_five_move_draw());
+
+        let game = Game::from_fen("8/8/4k3/8/8/3K4/8/7R w - - 150 105").unwrap();
+        assert!(game.is_seventy_five_move_draw());
+    }
+
+    #[test]
+    fn checkmate_should_take_precedence() {
+        let game = Game::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 100 80").unwrap();
+        assert!(!game.is_fifty_move_draw_claimable());
+
+        let game = Game::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 150 105").unwrap();
+        assert!(!game.is_seventy_five_move_draw());
+    }
+
+    #[test]
+    fn should_detect_insufficient_material() {
+        let insufficient = [
+            "8/8/4k3/8/8/3K4/8/8 w - -",
+            "8/8/4k3/8/8/3K4/8/6N1 w - -",
+            "8/8/4k3/8/8/3K4/8/5b2 w - -",
+            "8/8/2b1k3/8/8/3K4/8/5B2 w - -",
+            "8/8/2b1k3/8/2b5/3K4/8/5B2 w - -",
+        ];
+        let sufficient = [
+            "8/8/4k3/8/8/3K4/4P3/8 w - -",
+            "8/8/4k3/8/8/3K4/8/4R3 w - -",
+            "8/8/3bk3/8/8/3K4/8/5B2 w - -",
+            "8/8/4k3/8/8/3K4/8/4NN2 w - -",
+            "8/8/4k1n1/8/8/3K4/8/6N1 w - -",
+        ];
+
+        for fen in insufficient {
+            assert!(
+                Game::from_fen(fen).unwrap().is_insufficient_material(),
+                "{}",
+                fen
+            );
+        }
+        for fen in sufficient {
+            assert!(
+                !Game::from_fen(fen).unwrap().is_insufficient_material(),
+                "{}",
+                fen
+            );
+        }
+    }
+
+    #[test]
+    fn should_detect_mating_material() {
+        // King and knight against king and pawn, the pawn can block its own king
+        let game = Game::from_fen("8/8/4k3/4p3/8/3K4/8/6N1 w - -").unwrap();
+        assert!(game.has_mating_material(Color::White));
+        assert!(game.has_mating_material(Color::Black));
+
+        // King and knight against king and queen
+        let game = Game::from_fen("8/8/4k3/4q3/8/3K4/8/6N1 w - -").unwrap();
+        assert!(!game.has_mating_material(Color::White));
+        assert!(game.has_mating_material(Color::Black));
+
+        // King and bishop against king and same colored bishop
+        let game = Game::from_fen("8/8/2b1k3/8/8/3K4/8/5B2 w - -").unwrap();
+        assert!(!game.has_mating_material(Color::White));
+        assert!(!game.has_mating_material(Color::Black));
+
+        // King and bishop against king and opposite colored bishop
+        let game = Game::from_fen("8/8/3bk3/8/8/3K4/8/5B2 w - -").unwrap();
+        assert!(game.has_mating_material(Color::White));
+
+        // Lone king
+        let game = Game::from_fen("8/8/4k3/8/8/3K4/8/4R3 w - -").unwrap();
+        assert!(!game.has_mating_material(Color::Black));
+    }
+}
@@ -1,72 +1,74 @@
-use std::collections::HashSet;
-
-use crate::{error::FromFenError, Board, Color, Game, PieceType};
+use crate::{error::FromFenError, Board, Color, File, Game, PieceType, Rank, Square};
 
 impl Game {
     /// Creates a new game from a FEN string
     ///
+    /// Both the full six field form and the shorter four field form without the halfmove clock
+    /// and fullmove number are accepted. If the clocks are left out they default to `0` and `1`.
+    /// The fields can be separated by any amount of whitespace. Any input is accepted without
+    /// panicking, invalid FEN strings return an error that points to the field and character
+    /// offset of the problem.
+    ///
     /// # Arguments
     /// * `fen` - A string that holds the FEN string
     ///
     /// # Returns
     /// * `Result<game, FromFenError>` - A result that holds the game if the fen string is valid
-    /// or an error if the FEN string is invalid
+    ///   or an error if the FEN string is invalid
     ///
     /// # Examples
     /// ```
     /// use fritiofr_chess::Game;
     ///
     /// // Starting position
-    /// let game = Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -");
+    /// let game = Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
     /// ```
     pub fn from_fen(fen: &str) -> Result<Game, FromFenError> {
-        let fen_parts = fen.split(' ').collect::<Vec<&str>>();
+        let fen_parts = fields(fen);
 
-        let fen_part_pieces = fen_parts[0];
-        let fen_part_turn = fen_parts[1];
-        let fen_part_castling = fen_parts[2];
-        let fen_part_en_passant = fen_parts[3];
+        if fen_parts.len() != 4 && fen_parts.len() != 6 {
+            let (field, offset) = match fen_parts.get(6) {
+                Some((offset, _)) => (6, *offset),
+                None => (fen_parts.len(), fen.chars().count()),
+            };
 
-        if fen_parts.len() != 4 {
-            return Err(FromFenError::IncorrectAmountOfParts);
+            return Err(FromFenError::IncorrectAmountOfParts { field, offset });
         }
 
-        let board = Board::from_fen(fen_part_pieces)?;
+        let (offset, fen_part_pieces) = fen_parts[0];
+        let board = Board::parse_fen(fen_part_pieces, offset)?;
 
-        let turn = match fen_part_turn {
-            "w" => Color::White,
-            "b" => Color::Black,
-            _ => return Err(FromFenError::UnknownTurn),
+        let turn = match fen_parts[1] {
+            (_, "w") => Color::White,
+            (_, "b") => Color::Black,
+            (offset, _) => return Err(FromFenError::UnknownTurn { field: 1, offset }),
         };
 
-        let castling = castling_part(fen_part_castling)?;
+        let (offset, fen_part_castling) = fen_parts[2];
+        let castling = castling_part(fen_part_castling, offset)?;
 
-        let en_passant = en_passant(fen_part_en_passant)?;
-
-        let en_passant = if let Some((ep_x, ep_y)) = en_passant {
-            // Because i store en passant as the tile of the pawn that can be captured,
-            let ep_y = if turn == Color::White {
-                ep_y + 1
-            } else {
-                ep_y - 1
-            };
+        let (offset, fen_part_en_passant) = fen_parts[3];
+        let en_passant = en_passant(fen_part_en_passant, offset, turn, &board)?;
 
-            let ocp_piece = board.get_tile(ep_x, ep_y);
+        let (halfmove_clock, fullmove_number) = if fen_parts.len() == 6 {
+            let (offset, fen_part_halfmove_clock) = fen_parts[4];
+            let halfmove_clock = fen_part_halfmove_clock
+                .parse::<u32>()
+                .map_err(|_| FromFenError::InvalidHalfmoveClock { field: 4, offset })?;
 
-            if let Some(piece) = ocp_piece {
-                if piece.piece_type != PieceType::Pawn || piece.color == turn {
-                    return Err(FromFenError::InvalidEnPassant);
-                }
-            } else {
-                return Err(FromFenError::InvalidEnPassant);
-            }
+            let (offset, fen_part_fullmove_number) = fen_parts[5];
+            let fullmove_number = fen_part_fullmove_number
+                .parse::<u32>()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or(FromFenError::InvalidFullmoveNumber { field: 5, offset })?;
 
-            Some((ep_x, ep_y))
+            (halfmove_clock, fullmove_number)
         } else {
-            None
+            (0, 1)
         };
 
-        Ok(Game {
+        let mut game = Game {
             board,
             turn,
             en_passant,
@@ -74,11 +76,22 @@ impl Game {
             white_queenside_castle: castling[1],
             black_kingside_castle: castling[2],
             black_queenside_castle: castling[3],
-        })
+            halfmove_clock,
+            fullmove_number,
+            hash: 0,
+            history: Vec::new(),
+            position_keys: Vec::new(),
+            redo: Vec::new(),
+        };
+        game.hash = game.compute_hash();
+
+        Ok(game)
     }
 
     /// Returns the game as a FEN string
     ///
+    /// The FEN string always has all six fields, including the halfmove clock and fullmove number
+    ///
     /// # Returns
     /// * `String` - The game as a FEN string
     pub fn fen(&self) -> String {
@@ -119,97 +132,152 @@ impl Game {
                 ep_y + 1
             };
 
-            let rank = '1' as usize + (7 - ep_y);
-            let file = 'a' as usize + ep_x;
-
-            format!("{}{}", char::from(file as u8), char::from(rank as u8))
+            Square::try_from((ep_x, ep_y))
+                .expect("En passant is always on the board")
+                .to_string()
         } else {
             "-".to_string()
         };
 
-        format!("{} {} {} {}", board, turn, castling, en_passant)
+        format!(
+            "{} {} {} {} {} {}",
+            board, turn, castling, en_passant, self.halfmove_clock, self.fullmove_number
+        )
+    }
+}
+
+/// Internal helper that splits a FEN string into its fields, any amount of whitespace separates
+/// two fields
+///
+/// # Returns
+/// * `Vec<(usize, &str)>` - The fields together with the character offset they start at
+fn fields(fen: &str) -> Vec<(usize, &str)> {
+    let mut fields = vec![];
+    // The byte index and character offset of the field that is being read
+    let mut start = None;
+
+    for (offset, (i, c)) in fen.char_indices().enumerate() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some((i, offset)),
+            (Some((start_i, start_offset)), true) => {
+                fields.push((start_offset, &fen[start_i..i]));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+
+    if let Some((start_i, start_offset)) = start {
+        fields.push((start_offset, &fen[start_i..]));
     }
+
+    fields
 }
 
-fn castling_part(fen_part: &str) -> Result<[bool; 4], FromFenError> {
+fn castling_part(fen_part: &str, start: usize) -> Result<[bool; 4], FromFenError> {
     if fen_part == "-" {
         return Ok([false; 4]);
     }
 
     let mut castling: [bool; 4] = [false; 4];
-    let chars = fen_part.chars().collect::<Vec<char>>();
 
-    if chars.len() > 4 {
-        return Err(FromFenError::IncorrectLength);
-    }
+    for (i, c) in fen_part.chars().enumerate() {
+        let offset = start + i;
 
-    if chars.len() != chars.iter().collect::<HashSet<&char>>().len() {
-        return Err(FromFenError::RepeatingCharactersInCastlingPart);
-    }
+        if i >= 4 {
+            return Err(FromFenError::IncorrectLength { field: 2, offset });
+        }
+
+        let index = match c {
+            'K' => 0,
+            'Q' => 1,
+            'k' => 2,
+            'q' => 3,
+            _ => return Err(FromFenError::UnknownCharacter { field: 2, offset }),
+        };
 
-    for c in chars {
-        match c {
-            'K' => castling[0] = true,
-            'Q' => castling[1] = true,
-            'k' => castling[2] = true,
-            'q' => castling[3] = true,
-            _ => return Err(FromFenError::UnknownCharacter),
+        if castling[index] {
+            return Err(FromFenError::RepeatingCharactersInCastlingPart { field: 2, offset });
         }
+        castling[index] = true;
     }
 
     Ok(castling)
 }
 
-fn en_passant(fen_part: &str) -> Result<Option<(usize, usize)>, FromFenError> {
+/// Internal helper that parses the en passant part of a FEN string
+///
+/// # Returns
+/// * `Result<Option<(usize, usize)>, FromFenError>` - The tile of the pawn that can be captured
+///   en passant, or an error if there is no such pawn
+fn en_passant(
+    fen_part: &str,
+    start: usize,
+    turn: Color,
+    board: &Board,
+) -> Result<Option<(usize, usize)>, FromFenError> {
     if fen_part == "-" {
         return Ok(None);
     }
 
-    let chars = fen_part.chars().collect::<Vec<char>>();
+    let mut chars = fen_part.chars();
 
-    if chars.len() != 2 {
-        return Err(FromFenError::IncorrectAmountOfTiles);
-    }
+    let square = match (chars.next(), chars.next(), chars.next()) {
+        (Some(file), Some(rank), None) => {
+            let file = File::from_char(file).ok_or(FromFenError::UnknownCharacter {
+                field: 3,
+                offset: start,
+            })?;
+            let rank = Rank::from_char(rank).ok_or(FromFenError::UnknownCharacter {
+                field: 3,
+                offset: start + 1,
+            })?;
 
-    let file = match chars[0] {
-        'a' => 0,
-        'b' => 1,
-        'c' => 2,
-        'd' => 3,
-        'e' => 4,
-        'f' => 5,
-        'g' => 6,
-        'h' => 7,
-        _ => return Err(FromFenError::UnknownCharacter),
+            Square::new(file, rank)
+        }
+        _ => {
+            return Err(FromFenError::IncorrectAmountOfTiles {
+                field: 3,
+                offset: start,
+            })
+        }
     };
 
-    // Yes, this is super odd, but i accidentally made the board uppside down, oops...
-    let rank = match chars[1] {
-        '1' => 7,
-        '2' => 6,
-        '3' => 5,
-        '4' => 4,
-        '5' => 3,
-        '6' => 2,
-        '7' => 1,
-        '8' => 0,
-        _ => return Err(FromFenError::UnknownCharacter),
+    // Because i store en passant as the tile of the pawn that can be captured, the pawn has to
+    // be found one rank closer to the middle than the square in the FEN string
+    let (en_passant_rank, pawn_rank) = match turn {
+        Color::White => (Rank::SIXTH, Rank::FIFTH),
+        Color::Black => (Rank::THIRD, Rank::FOURTH),
     };
-
-    Ok(Some((file, rank)))
+    let pawn = Square::new(square.file(), pawn_rank);
+
+    match board.piece_at(pawn) {
+        Some(piece)
+            if square.rank() == en_passant_rank
+                && piece.piece_type == PieceType::Pawn
+                && piece.color != turn =>
+        {
+            Ok(Some(pawn.coords()))
+        }
+        _ => Err(FromFenError::InvalidEnPassant {
+            field: 3,
+            offset: start,
+        }),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Move;
 
     #[test]
     pub fn fen_should_be_same_as_from_fen() {
         let fens_to_test = vec![
-            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -",
-            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b - -",
-            "5bnr/pp1p1ppp/nbrp4/1k2pQN1/2B1q3/6N1/PPPRPPPP/R1B1K3 w Q e6",
-            "rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq c3",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b - - 3 18",
+            "5bnr/pp1p1ppp/nbrp4/1k2pQN1/2B1q3/6N1/PPPRPPPP/R1B1K3 w Q e6 0 24",
+            "rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq c3 0 1",
         ];
 
         for fen in fens_to_test {
@@ -217,4 +285,182 @@ mod tests {
             assert_eq!(board.fen(), fen);
         }
     }
+
+    #[test]
+    pub fn four_part_fen_should_default_the_clocks() {
+        let game =
+            Game::from_fen("rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq c3").unwrap();
+
+        assert_eq!(game.get_halfmove_clock(), 0);
+        assert_eq!(game.get_fullmove_number(), 1);
+        assert_eq!(
+            game.fen(),
+            "rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq c3 0 1"
+        );
+    }
+
+    #[test]
+    pub fn clocks_should_not_affect_equality() {
+        let game =
+            Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 12 40").unwrap();
+
+        assert_eq!(game, Game::start_pos());
+        assert_ne!(game.fen(), Game::start_pos().fen());
+    }
+
+    #[test]
+    pub fn invalid_clocks_should_be_rejected() {
+        assert!(matches!(
+            Game::from_fen("8/8/8/4k3/8/8/8/4K3 w - - x 1"),
+            Err(FromFenError::InvalidHalfmoveClock {
+                field: 4,
+                offset: 26
+            })
+        ));
+        assert!(matches!(
+            Game::from_fen("8/8/8/4k3/8/8/8/4K3 w - - 0 0"),
+            Err(FromFenError::InvalidFullmoveNumber {
+                field: 5,
+                offset: 28
+            })
+        ));
+        assert!(matches!(
+            Game::from_fen("8/8/8/4k3/8/8/8/4K3 w - - 0"),
+            Err(FromFenError::IncorrectAmountOfParts {
+                field: 5,
+                offset: 27
+            })
+        ));
+    }
+
+    #[test]
+    pub fn errors_should_point_to_the_problem() {
+        let error = |fen: &str| {
+            let error = Game::from_fen(fen).unwrap_err();
+            (error.field(), error.offset())
+        };
+
+        assert_eq!(error(""), (0, 0));
+        assert_eq!(error("8/8/8/8/8/8/8/8"), (1, 15));
+        assert_eq!(error("8/8/8/8/8/8/8/8 w - - 0 1 extra"), (6, 26));
+        assert_eq!(error("8/8/8/4x3/8/8/8/4K3 w - -"), (0, 7));
+        assert_eq!(error("8/8/8/4k4/8/8/8/4K3 w - -"), (0, 8));
+        assert_eq!(error("8/8/8/4k3/8/8/8 w - -"), (0, 15));
+        assert_eq!(error("8/8/8/4k3/8/8/8/4K3 x - -"), (1, 20));
+        assert_eq!(error("8/8/8/4k3/8/8/8/4K3 w KQkqK -"), (2, 26));
+        assert_eq!(error("8/8/8/4k3/8/8/8/4K3 w KK -"), (2, 23));
+        assert_eq!(error("8/8/8/4k3/8/8/8/4K3 w - e9"), (3, 25));
+        assert_eq!(error("8/8/8/4k3/8/8/8/4K3 b - e8"), (3, 24));
+        assert_eq!(error("8/8/8/4k3/8/8/8/4K3 w - e6"), (3, 24));
+    }
+
+    #[test]
+    pub fn extra_whitespace_should_be_ignored() {
+        let game = Game::from_fen("  8/8/8/4k3/8/8/8/4K3\tw  -\n-   3 40 ").unwrap();
+
+        assert_eq!(game.fen(), "8/8/8/4k3/8/8/8/4K3 w - - 3 40");
+    }
+
+    #[test]
+    pub fn from_fen_should_never_panic() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq c3 0 1",
+            "5bnr/pp1p1ppp/nbrp4/1k2pQN1/2B1q3/6N1/PPPRPPPP/R1B1K3 w Q e6 0 24",
+        ];
+        let replacements = [
+            ' ', '/', '-', '0', '1', '8', '9', 'e', 'K', 'p', 'w', 'b', 'é', '\n',
+        ];
+
+        for fen in fens {
+            let chars = fen.chars().collect::<Vec<char>>();
+
+            for i in 0..chars.len() {
+                let _ = Game::from_fen(&chars[..i].iter().collect::<String>());
+
+                for c in replacements {
+                    let mut changed = chars.clone();
+                    changed[i] = c;
+                    let _ = Game::from_fen(&changed.iter().collect::<String>());
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn apply_move_should_update_the_clocks() {
+        let mut game =
+            Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+
+        game.apply_move(Move::Quiet {
+            from: (6, 7),
+            to: (5, 5),
+        })
+        .unwrap();
+        assert_eq!(
+            (game.get_halfmove_clock(), game.get_fullmove_number()),
+            (1, 1)
+        );
+
+        game.apply_move(Move::Quiet {
+            from: (6, 0),
+            to: (5, 2),
+        })
+        .unwrap();
+        assert_eq!(
+            (game.get_halfmove_clock(), game.get_fullmove_number()),
+            (2, 2)
+        );
+
+        game.apply_move(Move::DoublePawnPush {
+            from: (4, 6),
+            to: (4, 4),
+        })
+        .unwrap();
+        assert_eq!(
+            (game.get_halfmove_clock(), game.get_fullmove_number()),
+            (0, 2)
+        );
+
+        game.undo_move();
+        assert_eq!(
+            (game.get_halfmove_clock(), game.get_fullmove_number()),
+            (2, 2)
+        );
+        game.undo_move();
+        assert_eq!(
+            (game.get_halfmove_clock(), game.get_fullmove_number()),
+            (1, 1)
+        );
+    }
+
+    #[test]
+    pub fn apply_move_should_not_overflow_the_clocks() {
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 4294967295 4294967295").unwrap();
+
+        let mv = Move::Quiet {
+            from: (4, 0),
+            to: (4, 1),
+        };
+
+        game.apply_move(mv).unwrap();
+        assert_eq!(
+            (game.get_halfmove_clock(), game.get_fullmove_number()),
+            (u32::MAX, u32::MAX)
+        );
+
+        // Taking the move back restores the clocks exactly, both from the history and an undo
+        game.undo_move();
+        assert_eq!(
+            (game.get_halfmove_clock(), game.get_fullmove_number()),
+            (u32::MAX, u32::MAX)
+        );
+        assert_eq!(game.get_turn(), Color::Black);
+
+        let undo = game.make_move(mv).unwrap();
+        game.unmake_move(mv, undo);
+        assert_eq!(
+            game.fen(),
+            "4k3/8/8/8/8/8/8/4K3 b - - 4294967295 4294967295"
+        );
+    }
 }
@@ -0,0 +1,513 @@
+use crate::{
+    attacks, error::TooManyMovesError, Bitboard, Color, File, Game, Move, MoveList, Piece,
+    PieceType, Rank, Square, MAX_MOVES,
+};
+
+const PROMOTION_PIECES: [PieceType; 4] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+impl Game {
+    /// Generates every legal move for the current turn into a move list
+    ///
+    /// The list is cleared first. Unlike `gen_all_moves` no memory is allocated and the game is
+    /// never copied, checks and pinned pieces are found up front so every generated move is legal.
+    /// The exception is a position where the current turn doesn't have exactly one king, there
+    /// checks and pins don't apply, so every move is played on a copy of the game instead.
+    ///
+    /// # Arguments
+    /// * `moves` - The list to fill with the moves, empty if the current turn has no moves
+    ///
+    /// # Panics
+    /// If there are more than `MAX_MOVES` legal moves, which can only happen in made up positions
+    /// with lots of extra pieces. See `try_generate_legal_into` for a version that doesn't panic,
+    /// `gen_all_moves` has no such limit.
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, MoveList};
+    ///
+    /// // White is in check from the bishop, so it can't castle and the rook can't help
+    /// let game = Game::from_fen("6k1/8/8/8/8/2b5/8/R3K3 w Q - 0 1").unwrap();
+    /// let mut moves = MoveList::new();
+    ///
+    /// game.generate_legal_into(&mut moves);
+    /// assert!(moves.iter().all(|mv| mv.from() == (4, 7)));
+    /// assert_eq!(moves.len(), 4);
+    /// ```
+    pub fn generate_legal_into(&self, moves: &mut MoveList) {
+        self.try_generate_legal_into(moves)
+            .expect("The position has more legal moves than fit in a MoveList");
+    }
+
+    /// Generates every legal move for the current turn into a move list, see
+    /// `generate_legal_into`
+    ///
+    /// Positions set up from user input can have any amount of pieces, use this when a position
+    /// that doesn't fit in a `MoveList` shouldn't bring the program down.
+    ///
+    /// # Arguments
+    /// * `moves` - The list to fill with the moves, empty if the current turn has no moves
+    ///
+    /// # Returns
+    /// * `Result<(), TooManyMovesError>` - Nothing if the moves were generated, or an error if
+    ///   there are more than `MAX_MOVES` legal moves. The list is left empty on an error
+    pub fn try_generate_legal_into(&self, moves: &mut MoveList) -> Result<(), TooManyMovesError> {
+        moves.clear();
+
+        let mut list = BoundedList {
+            moves,
+            overflowed: false,
+        };
+
+        match self.only_king() {
+            Some(king) => self.gen_moves_into(&mut list, Some(king)),
+            // Without exactly one king checks and pins don't apply, so every move is tried on a copy
+            None => {
+                for mv in self.gen_legal_moves_without_king() {
+                    list.push(mv);
+                }
+            }
+        }
+
+        if list.overflowed {
+            list.moves.clear();
+            return Err(TooManyMovesError);
+        }
+
+        Ok(())
+    }
+
+    /// Internal helper that generates every legal move for the current turn into a vector
+    ///
+    /// Used instead of `generate_legal_into` when the moves are returned as a vector anyway, since
+    /// a vector can hold any amount of moves.
+    pub(crate) fn gen_legal_moves(&self) -> Vec<Move> {
+        match self.only_king() {
+            Some(king) => {
+                let mut moves = Vec::new();
+                self.gen_moves_into(&mut moves, Some(king));
+                moves
+            }
+            None => self.gen_legal_moves_without_king(),
+        }
+    }
+
+    /// Internal helper that returns the king of the current turn, if there is exactly one
+    fn only_king(&self) -> Option<Square> {
+        let mut kings = self.board.by_piece(self.turn, PieceType::King);
+
+        match (kings.next(), kings.next()) {
+            (Some(king), None) => Some(king),
+            _ => None,
+        }
+    }
+
+    /// Internal helper that generates the legal moves when there isn't exactly one king
+    ///
+    /// Checks and pins only make sense with exactly one king, so each move is played instead.
+    fn gen_legal_moves_without_king(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        self.gen_moves_into(&mut moves, None);
+
+        moves.retain(|mv| self.is_legal_without_king(*mv));
+        moves
+    }
+
+    /// Internal helper that plays a pseudo legal move on a copy of the game, and returns if it
+    /// leaves no king of the current turn in check
+    fn is_legal_without_king(&self, mv: Move) -> bool {
+        let mut game = self.without_history();
+        game.play_move(mv)
+            .expect("gen_moves_into only returns valid moves");
+
+        !game.can_capture_king(game.turn)
+    }
+
+    /// Internal helper that returns if the current turn has a legal en passant capture
+    ///
+    /// Only the pawns next to the en passant pawn are looked at, so this is a lot cheaper than
+    /// generating every legal move.
+    pub(crate) fn can_capture_en_passant(&self) -> bool {
+        let king = self.only_king();
+        let mut captures = Vec::new();
+        self.gen_en_passant_into(&mut captures, king);
+
+        match king {
+            Some(_) => !captures.is_empty(),
+            None => captures.iter().any(|mv| self.is_legal_without_king(*mv)),
+        }
+    }
+
+    /// Internal helper that generates the moves for the current turn
+    ///
+    /// # Arguments
+    /// * `moves` - The list to add the moves to
+    /// * `king` - The only king of the current turn, the moves are legal if it's set. If it's
+    ///   None the moves are pseudo legal, they might leave a king in check
+    fn gen_moves_into(&self, moves: &mut impl MoveSink, king: Option<Square>) {
+        let us = self.turn;
+        let them = us.opposite();
+        let board = &self.board;
+        let own = board.by_color(us);
+        let enemy = board.by_color(them);
+        let occupied = board.occupied();
+
+        let checkers = king.map_or(Bitboard::EMPTY, |king| board.attackers(king, them));
+        let pinned = king.map_or(Bitboard::EMPTY, |king| self.pinned(king));
+
+        // The squares that pieces other than the king can move to, when in check they have to
+        // capture the checking piece or block it
+        let targets = match (king, checkers.first()) {
+            (Some(king), Some(checker)) if checkers.count() == 1 => {
+                attacks::between(king, checker) | Bitboard::from(checker)
+            }
+            (_, Some(_)) => Bitboard::EMPTY,
+            (_, None) => Bitboard::FULL,
+        };
+        // A pinned piece can only move along the line between the king and the pinning piece
+        let allowed = |from: Square| match king {
+            Some(king) if pinned.contains(from) => targets & attacks::line(king, from),
+            _ => targets,
+        };
+
+        for from in board.by_piece(us, PieceType::King) {
+            let mut to = attacks::king_attacks(from) - own;
+
+            if king.is_some() {
+                // The king is removed so it doesn't block a slider from attacking the squares
+                // behind it
+                let without_king = occupied - Bitboard::from(from);
+                to = to
+                    .filter(|to| board.attackers_through(*to, them, without_king).is_empty())
+                    .collect();
+            }
+
+            push_moves(moves, from, to, enemy);
+        }
+
+        for from in board.by_piece(us, PieceType::Knight) {
+            push_moves(
+                moves,
+                from,
+                (attacks::knight_attacks(from) - own) & allowed(from),
+                enemy,
+            );
+        }
+
+        let queens = board.by_piece(us, PieceType::Queen);
+        for from in board.by_piece(us, PieceType::Bishop) | queens {
+            let to = (attacks::bishop_attacks(from, occupied) - own) & allowed(from);
+            push_moves(moves, from, to, enemy);
+        }
+        for from in board.by_piece(us, PieceType::Rook) | queens {
+            let to = (attacks::rook_attacks(from, occupied) - own) & allowed(from);
+            push_moves(moves, from, to, enemy);
+        }
+
+        self.gen_pawn_moves_into(moves, king, &allowed);
+
+        if checkers.is_empty() {
+            self.gen_castling_into(moves);
+        }
+    }
+
+    /// Internal helper that generates the pawn moves for the current turn
+    ///
+    /// # Arguments
+    /// * `moves` - The list to add the moves to
+    /// * `king` - The only king of the current turn, see `gen_moves_into`
+    /// * `allowed` - The squares each pawn can move to without leaving the king in check
+    fn gen_pawn_moves_into(
+        &self,
+        moves: &mut impl MoveSink,
+        king: Option<Square>,
+        allowed: &impl Fn(Square) -> Bitboard,
+    ) {
+        let us = self.turn;
+        let them = us.opposite();
+        let board = &self.board;
+        let occupied = board.occupied();
+        let pawns = board.by_piece(us, PieceType::Pawn);
+
+        let (dir, starting_rank) = match us {
+            Color::White => (1, Rank::SECOND),
+            Color::Black => (-1, Rank::SEVENTH),
+        };
+
+        for from in pawns {
+            let allowed = allowed(from);
+
+            if let Some(to) = from.offset(0, dir).filter(|to| !occupied.contains(*to)) {
+                if allowed.contains(to) {
+                    push_pawn_moves(moves, from, to, None);
+                }
+
+                let double = to
+                    .offset(0, dir)
+                    .filter(|double| !occupied.contains(*double) && allowed.contains(*double));
+
+                if let Some(double) = double.filter(|_| from.rank() == starting_rank) {
+                    moves.push(Move::new_double_pawn_push(from, double));
+                }
+            }
+
+            for to in attacks::pawn_attacks(us, from) & board.by_color(them) & allowed {
+                push_pawn_moves(moves, from, to, Some(to));
+            }
+        }
+
+        self.gen_en_passant_into(moves, king);
+    }
+
+    /// Internal helper that generates the en passant captures for the current turn
+    ///
+    /// # Arguments
+    /// * `moves` - The list to add the moves to
+    /// * `king` - The only king of the current turn, see `gen_moves_into`
+    fn gen_en_passant_into(&self, moves: &mut impl MoveSink, king: Option<Square>) {
+        let us = self.turn;
+        let them = us.opposite();
+        let board = &self.board;
+        let occupied = board.occupied();
+        let pawns = board.by_piece(us, PieceType::Pawn);
+        let dir = match us {
+            Color::White => 1,
+            Color::Black => -1,
+        };
+
+        let Some(captured) = self.en_passant.and_then(|(x, y)| Square::from_coords(x, y)) else {
+            return;
+        };
+        let Some(to) = captured.offset(0, dir) else {
+            return;
+        };
+
+        // The pawns that attack the square behind the captured pawn
+        for from in attacks::pawn_attacks(them, to) & pawns {
+            // Two pieces leave the rank at once, so the king is checked on the board after the
+            // capture instead of with pins
+            let is_legal = king.is_none_or(|king| {
+                let after = (occupied - Bitboard::from(from) - Bitboard::from(captured))
+                    | Bitboard::from(to);
+
+                (board.attackers_through(king, them, after) - Bitboard::from(captured)).is_empty()
+            });
+
+            if is_legal {
+                moves.push(Move::new_capture(from, to, captured));
+            }
+        }
+    }
+
+    /// Internal helper that generates the castling moves for the current turn, the king must not
+    /// be in check
+    fn gen_castling_into(&self, moves: &mut impl MoveSink) {
+        let us = self.turn;
+        let board = &self.board;
+
+        let (rank, kingside, queenside) = match us {
+            Color::White => (
+                Rank::FIRST,
+                self.white_kingside_castle,
+                self.white_queenside_castle,
+            ),
+            Color::Black => (
+                Rank::EIGHTH,
+                self.black_kingside_castle,
+                self.black_queenside_castle,
+            ),
+        };
+        let piece = |piece_type| {
+            Some(Piece {
+                piece_type,
+                color: us,
+            })
+        };
+
+        let from = Square::new(File::E, rank);
+        if board.piece_at(from) != piece(PieceType::King) {
+            return;
+        }
+
+        // (castling right, rook start file, king end file, rook end file)
+        let sides = [
+            (kingside, File::H, File::G, File::F),
+            (queenside, File::A, File::C, File::D),
+        ];
+
+        for (right, rook_from, to, rook_to) in sides {
+            let rook_from = Square::new(rook_from, rank);
+            let to = Square::new(to, rank);
+
+            // The squares the king passes, including where it starts and ends
+            let path = attacks::between(from, to) | Bitboard::from(from) | Bitboard::from(to);
+
+            let can_castle = right
+                && board.piece_at(rook_from) == piece(PieceType::Rook)
+                && (attacks::between(from, rook_from) & board.occupied()).is_empty()
+                && !path
+                    .into_iter()
+                    .any(|square| board.is_attacked(square, us.opposite()));
+
+            if can_castle {
+                moves.push(Move::new_castle(
+                    from,
+                    to,
+                    rook_from,
+                    Square::new(rook_to, rank),
+                ));
+            }
+        }
+    }
+
+    /// Internal helper that returns the pieces of the current turn that are pinned to the king
+    fn pinned(&self, king: Square) -> Bitboard {
+        let them = self.turn.opposite();
+        let board = &self.board;
+        let enemy = board.by_color(them);
+        let queens = board.by_piece(them, PieceType::Queen);
+
+        // Enemy sliders that would attack the king if only the enemy pieces were on the board
+        let snipers = (attacks::rook_attacks(king, enemy)
+            & (board.by_piece(them, PieceType::Rook) | queens))
+            | (attacks::bishop_attacks(king, enemy)
+                & (board.by_piece(them, PieceType::Bishop) | queens));
+
+        snipers
+            .map(|sniper| attacks::between(king, sniper) & board.occupied())
+            .filter(|blockers| blockers.count() == 1)
+            .fold(Bitboard::EMPTY, |pinned, blockers| pinned | blockers)
+            & board.by_color(self.turn)
+    }
+}
+
+/// Internal trait for the lists that moves can be generated into
+trait MoveSink {
+    fn push(&mut self, mv: Move);
+}
+
+/// Internal wrapper around a move list that notes when a move doesn't fit instead of panicking
+struct BoundedList<'a> {
+    moves: &'a mut MoveList,
+    overflowed: bool,
+}
+
+impl MoveSink for BoundedList<'_> {
+    fn push(&mut self, mv: Move) {
+        if self.moves.len() < MAX_MOVES {
+            self.moves.push(mv);
+        } else {
+            self.overflowed = true;
+        }
+    }
+}
+
+impl MoveSink for Vec<Move> {
+    fn push(&mut self, mv: Move) {
+        Vec::push(self, mv);
+    }
+}
+
+/// Internal helper that adds a move to each square, a capture if an enemy piece is on it
+fn push_moves(moves: &mut impl MoveSink, from: Square, to: Bitboard, enemy: Bitboard) {
+    for to in to {
+        if enemy.contains(to) {
+            moves.push(Move::new_capture(from, to, to));
+        } else {
+            moves.push(Move::new_quiet(from, to));
+        }
+    }
+}
+
+/// Internal helper that adds a pawn move, or one move for each promotion piece if the pawn
+/// reaches the last rank
+fn push_pawn_moves(moves: &mut impl MoveSink, from: Square, to: Square, capture: Option<Square>) {
+    let last_rank = to.rank() == Rank::FIRST || to.rank() == Rank::EIGHTH;
+
+    match (capture, last_rank) {
+        (None, false) => moves.push(Move::new_quiet(from, to)),
+        (Some(capture), false) => moves.push(Move::new_capture(from, to, capture)),
+        (None, true) => {
+            for promotion in PROMOTION_PIECES {
+                moves.push(Move::new_quiet_promotion(from, to, promotion));
+            }
+        }
+        (Some(capture), true) => {
+            for promotion in PROMOTION_PIECES {
+                moves.push(Move::new_capture_promotion(from, to, capture, promotion));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legal_moves(fen: &str) -> MoveList {
+        let mut moves = MoveList::new();
+        Game::from_fen(fen).unwrap().generate_legal_into(&mut moves);
+        moves
+    }
+
+    #[test]
+    fn pinned_pieces_should_stay_on_the_pin() {
+        // The bishop is pinned by the rook and the knight by the queen, so neither can move
+        let moves = legal_moves("4r1k1/8/8/q7/8/2N5/4B3/4K3 w - - 0 1");
+        assert!(moves.iter().all(|mv| mv.from() == (4, 7)));
+
+        // The rook can move along the pin and capture the pinning rook
+        let moves = legal_moves("4r1k1/8/8/8/8/8/4R3/4K3 w - - 0 1");
+        assert_eq!(moves.iter().filter(|mv| mv.from() == (4, 6)).count(), 6);
+    }
+
+    #[test]
+    fn en_passant_should_not_expose_the_king() {
+        let moves = legal_moves("8/8/8/KPp4r/8/8/8/7k w - c6 0 1");
+
+        assert_eq!(moves.len(), 4);
+        assert!(moves.iter().all(|mv| !mv.is_capture()));
+    }
+
+    #[test]
+    fn double_check_should_only_allow_king_moves() {
+        let moves = legal_moves("4k3/8/5N2/8/8/8/8/Q3R1K1 b - - 0 1");
+
+        assert!(moves.iter().all(|mv| mv.from() == (4, 0)));
+        assert_eq!(moves.len(), 3);
+    }
+
+    #[test]
+    fn positions_without_a_king_should_still_have_moves() {
+        assert_eq!(legal_moves("8/8/8/8/8/8/8/R7 w - - 0 1").len(), 14);
+        assert_eq!(legal_moves("8/8/8/8/8/8/8/R3K2K w Q - 0 1").len(), 19);
+    }
+
+    #[test]
+    fn made_up_positions_should_not_overflow() {
+        let game = Game::from_fen("1QQQQQQk/Q7/2Q4Q/Q6Q/2Q4Q/Q5Q1/5Q2/QQQQQ2Q w - - 0 1").unwrap();
+        let moves = game.gen_all_moves().unwrap();
+
+        assert!(moves.len() > MAX_MOVES);
+        assert_eq!(
+            game.try_generate_legal_into(&mut MoveList::new()),
+            Err(TooManyMovesError)
+        );
+        assert_eq!(
+            game.gen_moves(1, 0).unwrap().len(),
+            moves.iter().filter(|mv| mv.from() == (1, 0)).count()
+        );
+
+        // Even with a king there can be too many moves in a made up position
+        let game = Game::from_fen("1QQQQQQQ/Q7/2Q4Q/Q6Q/2Q4Q/Q5Q1/5Q1K/QQQQQ2Q w - - 0 1").unwrap();
+        assert!(game.gen_all_moves().unwrap().len() > MAX_MOVES);
+        assert_eq!(
+            game.try_generate_legal_into(&mut MoveList::new()),
+            Err(TooManyMovesError)
+        );
+    }
+}
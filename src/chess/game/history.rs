@@ -0,0 +1,294 @@
+use crate::{Game, Move, Piece, PieceType};
+
+/// A move that has been applied to a game together with the state it replaced
+///
+/// This is what makes it possible to take a move back without keeping a copy of every position in
+/// the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// The move that was applied
+    pub mv: Move,
+    /// The piece that was captured by the move, if any
+    pub captured: Option<Piece>,
+    /// The castling rights before the move, in the order `[K, Q, k, q]`
+    pub castling: [bool; 4],
+    /// The en passant pawn before the move
+    pub en_passant: Option<(usize, usize)>,
+    /// The halfmove clock before the move
+    pub halfmove_clock: u32,
+    /// The fullmove number before the move
+    pub fullmove_number: u32,
+}
+
+impl Game {
+    /// Returns all the moves that have been applied to the game, oldest first
+    ///
+    /// # Returns
+    /// * `&[HistoryEntry]` - The applied moves and the state they replaced
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+
+    /// Returns the amount of half moves that have been applied to the game
+    ///
+    /// # Returns
+    /// * `usize` - The amount of moves in the history
+    pub fn ply(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Takes back the latest move in the game
+    ///
+    /// The move can be applied again with `redo_move`.
+    ///
+    /// # Returns
+    /// * `Option<Move>` - The move that was taken back, or None if there are no moves to undo
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, Move};
+    ///
+    /// let mut game = Game::start_pos();
+    /// game.apply_move(Move::Quiet { from: (4, 6), to: (4, 5) }).unwrap();
+    /// game.undo_move();
+    ///
+    /// assert_eq!(game, Game::start_pos());
+    /// ```
+    pub fn undo_move(&mut self) -> Option<Move> {
+        let entry = self.history.pop()?;
+        self.position_keys.pop();
+
+        self.take_back(&entry);
+        self.redo.push(entry.mv);
+
+        Some(entry.mv)
+    }
+
+    /// Applies the latest move that was taken back with `undo_move`
+    ///
+    /// # Returns
+    /// * `Option<Move>` - The move that was applied, or None if there are no moves to redo
+    pub fn redo_move(&mut self) -> Option<Move> {
+        let mv = self.redo.pop()?;
+
+        let position_key = self.position_key();
+        let entry = self
+            .play_move(mv)
+            .expect("The move was valid when it was first applied");
+        self.history.push(entry);
+        self.position_keys.push(position_key);
+
+        Some(mv)
+    }
+
+    /// Returns the position the game started from, before any of the moves in the history
+    ///
+    /// # Returns
+    /// * `Game` - The starting position, without any history
+    pub fn starting_position(&self) -> Game {
+        let mut game = self.without_history();
+
+        for entry in self.history.iter().rev() {
+            game.take_back(entry);
+        }
+
+        game
+    }
+
+    /// Internal helper that forgets every move, used when the position is replaced
+    pub(crate) fn clear_history(&mut self) {
+        self.history.clear();
+        self.position_keys.clear();
+        self.redo.clear();
+    }
+
+    /// Internal helper that restores the position from before a move
+    pub(crate) fn take_back(&mut self, entry: &HistoryEntry) {
+        let mv = entry.mv;
+        let (from_x, from_y) = mv.from();
+        let (to_x, to_y) = mv.to();
+
+        let mut piece = self
+            .board
+            .get_tile(to_x, to_y)
+            .expect("The moved piece is on the to tile");
+
+        if mv.is_promotion() {
+            piece.piece_type = PieceType::Pawn;
+        }
+
+        // The castling rights, en passant and turn are added back to the hash key at the end
+        self.hash ^= self.state_hash();
+
+        self.put_piece(to_x, to_y, None);
+        self.put_piece(from_x, from_y, Some(piece));
+
+        if let (Some(rook_from), Some(rook_to)) = (mv.rook_from(), mv.rook_to()) {
+            let rook = self
+                .board
+                .get_tile(rook_to.0, rook_to.1)
+                .expect("The castled rook is on the rook to tile");
+
+            self.put_piece(rook_to.0, rook_to.1, None);
+            self.put_piece(rook_from.0, rook_from.1, Some(rook));
+        }
+
+        if let (Some(captured), Some((c_x, c_y))) = (entry.captured, mv.capture()) {
+            self.put_piece(c_x, c_y, Some(captured));
+        }
+
+        self.white_kingside_castle = entry.castling[0];
+        self.white_queenside_castle = entry.castling[1];
+        self.black_kingside_castle = entry.castling[2];
+        self.black_queenside_castle = entry.castling[3];
+        self.en_passant = entry.en_passant;
+        self.halfmove_clock = entry.halfmove_clock;
+        self.fullmove_number = entry.fullmove_number;
+        self.turn = self.turn.opposite();
+        self.hash ^= self.state_hash();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    /// Plays through all the moves, checking that each one can be undone and redone
+    fn play_and_undo(fen: &str, moves: &[Move]) {
+        let mut game = Game::from_fen(fen).unwrap();
+        let mut fens = vec![game.fen()];
+
+        for mv in moves {
+            game.apply_move(*mv).unwrap();
+            fens.push(game.fen());
+        }
+
+        assert_eq!(game.ply(), moves.len());
+
+        for i in (0..moves.len()).rev() {
+            assert_eq!(game.undo_move(), Some(moves[i]));
+            assert_eq!(game.fen(), fens[i]);
+        }
+
+        assert!(game.undo_move().is_none());
+
+        for i in 0..moves.len() {
+            assert!(game.redo_move().is_some());
+            assert_eq!(game.fen(), fens[i + 1]);
+        }
+
+        assert!(game.redo_move().is_none());
+        assert_eq!(game.starting_position().fen(), fens[0]);
+    }
+
+    #[test]
+    fn undo_should_restore_captures_and_double_pushes() {
+        play_and_undo(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -",
+            &[
+                Move::DoublePawnPush {
+                    from: (4, 6),
+                    to: (4, 4),
+                },
+                Move::DoublePawnPush {
+                    from: (3, 1),
+                    to: (3, 3),
+                },
+                Move::Capture {
+                    from: (4, 4),
+                    to: (3, 3),
+                    capture: (3, 3),
+                },
+                Move::Capture {
+                    from: (3, 0),
+                    to: (3, 3),
+                    capture: (3, 3),
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn undo_should_restore_en_passant_castling_and_promotion() {
+        play_and_undo(
+            "r3k2r/1P6/8/8/3p4/8/4P3/R3K2R w KQkq -",
+            &[
+                Move::DoublePawnPush {
+                    from: (4, 6),
+                    to: (4, 4),
+                },
+                Move::Capture {
+                    from: (3, 4),
+                    to: (4, 5),
+                    capture: (4, 4),
+                },
+                Move::Castle {
+                    from: (4, 7),
+                    to: (6, 7),
+                    rook_from: (7, 7),
+                    rook_to: (5, 7),
+                },
+                Move::Castle {
+                    from: (4, 0),
+                    to: (2, 0),
+                    rook_from: (0, 0),
+                    rook_to: (3, 0),
+                },
+                Move::CapturePromotion {
+                    from: (1, 1),
+                    to: (0, 0),
+                    capture: (0, 0),
+                    promotion: PieceType::Queen,
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn apply_move_should_clear_redo() {
+        let mut game = Game::start_pos();
+        game.apply_move(Move::Quiet {
+            from: (4, 6),
+            to: (4, 5),
+        })
+        .unwrap();
+        game.undo_move();
+
+        game.apply_move(Move::Quiet {
+            from: (3, 6),
+            to: (3, 5),
+        })
+        .unwrap();
+
+        assert!(game.redo_move().is_none());
+        assert_eq!(game.history()[0].mv.from(), (3, 6));
+    }
+
+    #[test]
+    fn setters_should_clear_the_history() {
+        let e3 = Move::Quiet {
+            from: (4, 6),
+            to: (4, 5),
+        };
+
+        let mut game = Game::start_pos();
+        game.apply_move(e3).unwrap();
+        game.set_board(
+            Game::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1")
+                .unwrap()
+                .get_board(),
+        );
+
+        assert!(game.history().is_empty());
+        assert!(game.undo_move().is_none());
+
+        let mut game = Game::start_pos();
+        game.apply_move(e3).unwrap();
+        game.undo_move();
+        game.set_turn(Color::Black);
+
+        assert!(game.redo_move().is_none());
+        assert_eq!(game.starting_position(), game);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{error::GameApplyMoveError, Game, HistoryEntry, Move, Piece, Square};
+
+/// The state a move replaces, which `Game::unmake_move` needs to take the move back
+///
+/// Returned by `Game::make_move`. It only holds what can't be worked out from the move and the
+/// position after it, so it's cheap to keep one for every ply in a search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndoInfo {
+    /// The piece that was captured by the move, if any
+    captured: Option<Piece>,
+    /// The castling rights before the move, one bit each in the order `K`, `Q`, `k` and `q`
+    castling: u8,
+    /// The en passant pawn before the move
+    en_passant: Option<Square>,
+    /// The halfmove clock before the move
+    halfmove_clock: u32,
+    /// The fullmove number before the move
+    fullmove_number: u32,
+}
+
+impl UndoInfo {
+    /// Returns the piece that was captured by the move, if any
+    pub fn captured(&self) -> Option<Piece> {
+        self.captured
+    }
+}
+
+impl Game {
+    /// Applies a move to the game without recording it in the history
+    ///
+    /// This is the fast way to walk through positions, e.g in a search, since nothing is allocated
+    /// and the history isn't touched. The move is taken back with `unmake_move`. Moves made this
+    /// way aren't seen by `undo_move` or the repetition checks, so they should be unmade before
+    /// any of the history methods are used.
+    ///
+    /// # Arguments
+    /// * `mv` - The move to make
+    ///
+    /// # Returns
+    /// * `Result<UndoInfo, GameApplyMoveError>` - The state needed to unmake the move, or an error
+    ///   if the move was invalid
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, MoveList};
+    ///
+    /// let mut game = Game::start_pos();
+    /// let mut moves = MoveList::new();
+    /// game.generate_legal_into(&mut moves);
+    ///
+    /// for mv in &moves {
+    ///     let undo = game.make_move(*mv).unwrap();
+    ///     // Search the position after the move...
+    ///     game.unmake_move(*mv, undo);
+    /// }
+    ///
+    /// assert_eq!(game, Game::start_pos());
+    /// ```
+    pub fn make_move(&mut self, mv: Move) -> Result<UndoInfo, GameApplyMoveError> {
+        let entry = self.play_move(mv)?;
+
+        let castling = entry
+            .castling
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (i, right)| bits | (u8::from(*right) << i));
+
+        Ok(UndoInfo {
+            captured: entry.captured,
+            castling,
+            en_passant: entry
+                .en_passant
+                .and_then(|(x, y)| Square::from_coords(x, y)),
+            halfmove_clock: entry.halfmove_clock,
+            fullmove_number: entry.fullmove_number,
+        })
+    }
+
+    /// Takes back a move that was made with `make_move`
+    ///
+    /// Moves have to be unmade in the reverse order they were made, the game is then restored to
+    /// exactly the state it had before the move.
+    ///
+    /// # Arguments
+    /// * `mv` - The move to take back, the latest move that was made
+    /// * `undo` - The state that `make_move` returned for the move
+    pub fn unmake_move(&mut self, mv: Move, undo: UndoInfo) {
+        self.take_back(&HistoryEntry {
+            mv,
+            captured: undo.captured,
+            castling: [0, 1, 2, 3].map(|i| undo.castling & (1 << i) != 0),
+            en_passant: undo.en_passant.map(|square| square.coords()),
+            halfmove_clock: undo.halfmove_clock,
+            fullmove_number: undo.fullmove_number,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MoveList;
+
+    use super::*;
+
+    /// Makes and unmakes every move two plies deep and checks that the position is restored
+    fn make_and_unmake(game: &mut Game, depth: u8) {
+        if depth == 0 {
+            return;
+        }
+
+        let before = game.without_history();
+        let mut moves = MoveList::new();
+        game.generate_legal_into(&mut moves);
+
+        for mv in &moves {
+            let undo = game.make_move(*mv).unwrap();
+            make_and_unmake(game, depth - 1);
+            game.unmake_move(*mv, undo);
+
+            assert_eq!(*game, before, "{:?}", mv);
+            assert_eq!(game.fen(), before.fen());
+        }
+    }
+
+    #[test]
+    fn unmake_should_restore_the_position() {
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        ];
+
+        for fen in fens {
+            make_and_unmake(&mut Game::from_fen(fen).unwrap(), 2);
+        }
+    }
+
+    #[test]
+    fn undo_info_should_hold_the_capture() {
+        let mut game = Game::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        let mv = game.get_move(Square::E4, Square::D5).unwrap()[0];
+
+        let undo = game.make_move(mv).unwrap();
+        assert_eq!(undo.captured(), Piece::try_from('p').ok());
+        assert!(game.history().is_empty());
+    }
+}
@@ -1,12 +1,35 @@
+use std::hash::{Hash, Hasher};
+
 use crate::{Board, Color, PieceType};
 
 mod apply_move;
+mod builder;
+pub use builder::*;
+mod draw;
 mod fen;
-mod gen_pseudo_legal_moves;
+mod gen_legal_moves;
+mod history;
+pub use history::*;
+mod make_move;
+pub use make_move::*;
+mod outcome;
+pub use outcome::*;
+mod perft;
+pub use perft::*;
+mod repetition;
+pub use repetition::*;
+mod san;
+mod uci;
+mod validate;
+mod zobrist;
+
 use super::Move;
 
 /// A game of chess
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+///
+/// Besides the current position the game also keeps track of every move that has been applied to
+/// it, see `history`, `undo_move` and `redo_move`.
+#[derive(Clone, Debug)]
 pub struct Game {
     board: Board,
     turn: Color,
@@ -17,12 +40,26 @@ pub struct Game {
     white_queenside_castle: bool,
     black_kingside_castle: bool,
     black_queenside_castle: bool,
+
+    /// The amount of half moves since the last capture or pawn move
+    halfmove_clock: u32,
+    /// The number of the full move, starts at 1 and is incremented after black moves
+    fullmove_number: u32,
+    /// The Zobrist hash key of the position, see `hash_key`
+    hash: u64,
+
+    /// Every move that has been applied to the game, oldest first
+    history: Vec<HistoryEntry>,
+    /// The key of the position before each move in the history
+    position_keys: Vec<PositionKey>,
+    /// Moves that have been undone and can be redone, the next move to redo is last
+    redo: Vec<Move>,
 }
 
 impl Game {
     /// Returns a game with the starting position
     pub fn start_pos() -> Game {
-        Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -")
+        Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
             .expect("This fen string is valid")
     }
 
@@ -36,7 +73,9 @@ impl Game {
 
     /// Sets the Board for the game
     ///
-    /// **This will reset en passant and castling**
+    /// **This will reset en passant and castling**, use `PositionBuilder` to set up a position
+    /// with them. The move history is cleared as well, since the moves in it can't be undone on
+    /// the new board.
     ///
     /// # Arguments
     /// * `board` - The board to set
@@ -49,63 +88,83 @@ impl Game {
         self.en_passant = None;
 
         self.board = board;
+        self.hash = self.compute_hash();
+        self.clear_history();
     }
 
     /// Returns the current turn
     ///
     /// # Return
     /// * `Color` - The current turn
-    pub fn get_turn(&mut self) -> Color {
+    pub fn get_turn(&self) -> Color {
         self.turn
     }
 
     /// Sets the current turn
     ///
-    /// **This will reset en passant**
+    /// **This will reset en passant**, use `PositionBuilder` to set up a position with it. The
+    /// move history is cleared as well, since the moves in it can't be undone with another color
+    /// to move.
     ///
     /// # Arguments
     /// * `Color` - The current turn
     pub fn set_turn(&mut self, turn: Color) {
         self.en_passant = None;
         self.turn = turn;
+        self.hash = self.compute_hash();
+        self.clear_history();
+    }
+
+    /// Returns the halfmove clock
+    ///
+    /// # Return
+    /// * `u32` - The amount of half moves since the last capture or pawn move
+    pub fn get_halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    /// Returns the fullmove number
+    ///
+    /// # Return
+    /// * `u32` - The number of the current full move, starting at 1
+    pub fn get_fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
+    /// Returns a copy of the current position without any of the move history
+    ///
+    /// Used internally when a throwaway copy of the game is needed, since cloning the history
+    /// would mean an allocation for every copy.
+    pub(crate) fn without_history(&self) -> Game {
+        Game {
+            board: self.board,
+            turn: self.turn,
+            en_passant: self.en_passant,
+            white_kingside_castle: self.white_kingside_castle,
+            white_queenside_castle: self.white_queenside_castle,
+            black_kingside_castle: self.black_kingside_castle,
+            black_queenside_castle: self.black_queenside_castle,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            hash: self.hash,
+            history: Vec::new(),
+            position_keys: Vec::new(),
+            redo: Vec::new(),
+        }
     }
 
     /// Returns if a certain color can capture the other color's king
     fn can_capture_king(&self, color: Color) -> bool {
         self.board
-            .tiles
-            .iter()
-            .enumerate()
-            .filter(|(_, p)| p.is_some())
-            .map(|(i, p)| {
-                let x = i % 8;
-                let y = i / 8;
-
-                ((x, y), p.unwrap())
-            })
-            .filter(|(_, p)| p.color == color)
-            .map(|((x, y), _)| {
-                self.gen_pseudo_legal_moves(x, y, false)
-                    .unwrap_or(Vec::new())
-            })
-            .flatten()
-            .any(|m| match m {
-                Move::Capture { capture, .. } | Move::CapturePromotion { capture, .. } => {
-                    self.board
-                        .get_tile(capture.0, capture.1)
-                        .unwrap()
-                        .piece_type
-                        == PieceType::King
-                }
-                _ => false,
-            })
+            .by_piece(color.opposite(), PieceType::King)
+            .any(|king| self.board.is_attacked(king, color))
     }
 
     /// Returns if the current turn is in check
     ///
     /// # Returns
     /// * `bool` - If the current turn is in check, if it's black to move and black is in check,
-    /// this will return true
+    ///   this will return true
     pub fn is_check(&self) -> bool {
         self.can_capture_king(self.turn.opposite())
     }
@@ -114,7 +173,7 @@ impl Game {
     ///
     /// # Returns
     /// * `bool` - If the current turn is in checkmate, if it's black to move and black is in
-    /// checkmate, this will return true
+    ///   checkmate, this will return true
     pub fn is_checkmate(&self) -> bool {
         self.is_check() && self.gen_all_moves().is_none()
     }
@@ -123,7 +182,7 @@ impl Game {
     ///
     /// # Returns
     /// * `bool` - If the current turn is in stalemate, if it's black to move and black is in
-    /// stalemate, this will return true
+    ///   stalemate, this will return true
     pub fn is_stalemate(&self) -> bool {
         !self.is_check() && self.gen_all_moves().is_none()
     }
@@ -132,20 +191,11 @@ impl Game {
     ///
     /// # Returns
     /// * `Option<Vec<Move>>` - A vector of all the moves for the current turn, if there are no
-    /// moves, this will return None
+    ///   moves, this will return None
     pub fn gen_all_moves(&self) -> Option<Vec<Move>> {
-        let moves = (0..64)
-            .map(|i| {
-                let x = i % 8;
-                let y = i / 8;
-
-                (x, y)
-            })
-            .map(|(x, y)| self.gen_moves(x, y).unwrap_or(Vec::new()))
-            .flatten()
-            .collect::<Vec<Move>>();
+        let moves = self.gen_legal_moves();
 
-        if moves.len() == 0 {
+        if moves.is_empty() {
             return None;
         }
 
@@ -155,13 +205,20 @@ impl Game {
     /// Returns all moves that can be made from a square to a square
     ///
     /// # Arguments
-    /// * `from` - The coordinates of the from tile
-    /// * `to` - The coordinates of the to tile
+    /// * `from` - The from tile, either as coordinates or a `Square`
+    /// * `to` - The to tile, either as coordinates or a `Square`
     ///
     /// # Returns
     /// * `Option<Vec<Move>>` - A vector of all the possible moves, if there are no moves, this
-    /// will return None.
-    pub fn get_move(&self, from: (usize, usize), to: (usize, usize)) -> Option<Vec<Move>> {
+    ///   will return None.
+    pub fn get_move(
+        &self,
+        from: impl Into<(usize, usize)>,
+        to: impl Into<(usize, usize)>,
+    ) -> Option<Vec<Move>> {
+        let from = from.into();
+        let to = to.into();
+
         self.gen_moves(from.0, from.1).map(|mvs| {
             mvs.into_iter()
                 .filter(|mv| mv.to() == to)
@@ -177,40 +234,41 @@ impl Game {
     ///
     /// # Returns
     /// * `Option<Vec<Move>>` - A vector of all the moves for the tile, if there are no moves, this
-    /// will return None. If the piece of x and y is the opposite color of the current turn, this
-    /// will return None
+    ///   will return None. If the piece of x and y is the opposite color of the current turn, this
+    ///   will return None
     pub fn gen_moves(&self, x: usize, y: usize) -> Option<Vec<Move>> {
-        if let Some(piece) = self.board.get_tile(x, y) {
-            if piece.color != self.turn {
-                return None;
-            }
-        } else {
-            return None;
-        }
-
-        let moves = self.gen_pseudo_legal_moves(x, y, false);
-
-        if moves.is_none() {
-            return None;
-        }
-
-        let moves = moves
-            .unwrap()
+        let moves = self
+            .gen_all_moves()?
             .into_iter()
-            .filter(|m| {
-                let mut game = self.clone();
-                game.apply_move(*m)
-                    .expect("gen_pseudo_legal_moves only returns valid moves");
-
-                let can_cap = !game.can_capture_king(game.turn);
-                can_cap
-            })
+            .filter(|m| m.from() == (x, y))
             .collect::<Vec<Move>>();
 
-        if moves.len() == 0 {
+        if moves.is_empty() {
             return None;
         }
 
         Some(moves)
     }
 }
+
+/// Two games are equal if their current positions are equal, the clocks and the move history are
+/// not compared
+impl PartialEq for Game {
+    fn eq(&self, other: &Self) -> bool {
+        self.board == other.board
+            && self.turn == other.turn
+            && self.en_passant == other.en_passant
+            && self.white_kingside_castle == other.white_kingside_castle
+            && self.white_queenside_castle == other.white_queenside_castle
+            && self.black_kingside_castle == other.black_kingside_castle
+            && self.black_queenside_castle == other.black_queenside_castle
+    }
+}
+impl Eq for Game {}
+
+/// Hashes the current position with its Zobrist key, so equal games always have equal hashes
+impl Hash for Game {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{Color, Game};
+
+/// How a game of chess has ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// One of the colors has won the game
+    Decisive {
+        winner: Color,
+        reason: DecisiveReason,
+    },
+    /// The game is drawn
+    Draw { reason: DrawReason },
+}
+
+/// Why a game has been won
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecisiveReason {
+    Checkmate,
+}
+
+/// Why a game has been drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DrawReason {
+    Stalemate,
+    InsufficientMaterial,
+    SeventyFiveMoveRule,
+    FivefoldRepetition,
+}
+
+impl Outcome {
+    /// Returns the winner of the game
+    ///
+    /// # Returns
+    /// * `Option<Color>` - The color that won, or None if the game is drawn
+    pub fn winner(&self) -> Option<Color> {
+        match self {
+            Outcome::Decisive { winner, .. } => Some(*winner),
+            Outcome::Draw { .. } => None,
+        }
+    }
+}
+
+impl Game {
+    /// Returns how the game has ended
+    ///
+    /// Only the endings that happen automatically are reported, draws that have to be claimed
+    /// like the fifty-move rule or threefold repetition are checked with
+    /// `is_fifty_move_draw_claimable` and `is_threefold_repetition`.
+    ///
+    /// The legal moves are only generated once, so this is cheaper than calling `is_checkmate`,
+    /// `is_stalemate` and the draw checks one after another.
+    ///
+    /// # Returns
+    /// * `Option<Outcome>` - How the game ended, or None if the game is still going on
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Color, DecisiveReason, Game, Outcome};
+    ///
+    /// let game = Game::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 0 1").unwrap();
+    ///
+    /// assert_eq!(
+    ///     game.outcome(),
+    ///     Some(Outcome::Decisive {
+    ///         winner: Color::White,
+    ///         reason: DecisiveReason::Checkmate,
+    ///     })
+    /// );
+    /// ```
+    pub fn outcome(&self) -> Option<Outcome> {
+        let Some(moves) = self.gen_all_moves() else {
+            if self.is_check() {
+                return Some(Outcome::Decisive {
+                    winner: self.turn.opposite(),
+                    reason: DecisiveReason::Checkmate,
+                });
+            }
+
+            return Some(Outcome::Draw {
+                reason: DrawReason::Stalemate,
+            });
+        };
+
+        let reason = if self.is_insufficient_material() {
+            DrawReason::InsufficientMaterial
+        } else if self.halfmove_clock >= 150 {
+            DrawReason::SeventyFiveMoveRule
+        } else if self.repetitions_with_moves(&moves) >= 5 {
+            DrawReason::FivefoldRepetition
+        } else {
+            return None;
+        };
+
+        Some(Outcome::Draw { reason })
+    }
+
+    /// Returns if the game has ended
+    ///
+    /// # Returns
+    /// * `bool` - If the game has ended, see `outcome` for how
+    pub fn is_game_over(&self) -> bool {
+        self.outcome().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Move;
+
+    #[test]
+    fn should_report_the_outcome() {
+        let outcomes = [
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                None,
+            ),
+            (
+                "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+                Some(Outcome::Decisive {
+                    winner: Color::Black,
+                    reason: DecisiveReason::Checkmate,
+                }),
+            ),
+            (
+                "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1",
+                Some(Outcome::Draw {
+                    reason: DrawReason::Stalemate,
+                }),
+            ),
+            (
+                "8/8/4k3/8/8/3K4/8/6N1 w - - 0 1",
+                Some(Outcome::Draw {
+                    reason: DrawReason::InsufficientMaterial,
+                }),
+            ),
+            (
+                "8/8/4k3/8/8/3K4/8/7R w - - 150 105",
+                Some(Outcome::Draw {
+                    reason: DrawReason::SeventyFiveMoveRule,
+                }),
+            ),
+            ("8/8/4k3/8/8/3K4/8/7R w - - 149 105", None),
+        ];
+
+        for (fen, outcome) in outcomes {
+            let game = Game::from_fen(fen).unwrap();
+            assert_eq!(game.outcome(), outcome, "{}", fen);
+            assert_eq!(game.is_game_over(), outcome.is_some());
+        }
+    }
+
+    #[test]
+    fn should_report_fivefold_repetition() {
+        let mut game = Game::start_pos();
+
+        for i in 0..16 {
+            assert_eq!(game.outcome(), None);
+
+            let (from, to) = [
+                ((6, 7), (5, 5)),
+                ((6, 0), (5, 2)),
+                ((5, 5), (6, 7)),
+                ((5, 2), (6, 0)),
+            ][i % 4];
+            game.apply_move(Move::Quiet { from, to }).unwrap();
+        }
+
+        assert_eq!(
+            game.outcome(),
+            Some(Outcome::Draw {
+                reason: DrawReason::FivefoldRepetition,
+            })
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use std::{
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Game, Move, MoveList};
+
+/// Runs perft with several threads and a cache, and measures how long it takes
+///
+/// `Game::perft` is enough to check a move generator, this is for deeper runs where speed
+/// matters. The moves in the current position are shared out between the threads, and the cache
+/// remembers the count of positions that are reached through different move orders.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{Game, Perft};
+///
+/// let result = Perft::new(4).set_threads(2).set_cache_size(16).run(&Game::start_pos());
+///
+/// assert_eq!(result.nodes, 197281);
+/// assert_eq!(result.divide.len(), 20);
+/// println!("{} nodes per second", result.nps());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Perft {
+    depth: u32,
+    threads: usize,
+    /// The size of the cache in megabytes, 0 if there is no cache
+    cache_size: usize,
+}
+
+/// The result of running `Perft`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerftResult {
+    /// The amount of positions at the depth
+    pub nodes: u64,
+    /// Each legal move in UCI notation together with the amount of positions after it, in the
+    /// same order as `Game::perft_divide`
+    pub divide: Vec<(String, u64)>,
+    /// How long the run took
+    pub elapsed: Duration,
+}
+
+impl Perft {
+    /// Creates a perft run to a depth, with one thread and no cache
+    pub fn new(depth: u32) -> Perft {
+        Perft {
+            depth,
+            threads: 1,
+            cache_size: 0,
+        }
+    }
+
+    /// Sets the amount of threads to use, 0 uses one thread for each CPU
+    pub fn set_threads(&mut self, threads: usize) -> &mut Perft {
+        self.threads = threads;
+        self
+    }
+
+    /// Sets the size of the cache in megabytes, 0 turns the cache off
+    ///
+    /// The cache is keyed on the `hash_key` of a position and the remaining depth, two positions
+    /// with the same key would share a count, but with 64 bit keys that's very unlikely.
+    pub fn set_cache_size(&mut self, megabytes: usize) -> &mut Perft {
+        self.cache_size = megabytes;
+        self
+    }
+
+    /// Runs perft on the current position of a game
+    ///
+    /// # Returns
+    /// * `PerftResult` - The amount of positions, both in total and for each move
+    pub fn run(&self, game: &Game) -> PerftResult {
+        let start = Instant::now();
+
+        if self.depth == 0 {
+            return PerftResult {
+                nodes: 1,
+                divide: vec![],
+                elapsed: start.elapsed(),
+            };
+        }
+
+        let threads = match self.threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            threads => threads,
+        };
+        let cache = (self.cache_size > 0).then(|| PerftCache::new(self.cache_size));
+
+        let mut moves = MoveList::new();
+        game.generate_legal_into(&mut moves);
+
+        // The threads take the next move that no one has started on until all are done
+        let next = AtomicUsize::new(0);
+        let mut counts = vec![0; moves.len()];
+
+        thread::scope(|scope| {
+            let workers = (0..threads.min(moves.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut game = game.without_history();
+                        let mut counts = vec![];
+
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(mv) = moves.get(i) else {
+                                return counts;
+                            };
+
+                            counts.push((i, count_move(&mut game, *mv, self.depth, &cache)));
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            for worker in workers {
+                for (i, count) in worker.join().expect("A perft thread panicked") {
+                    counts[i] = count;
+                }
+            }
+        });
+
+        let divide = moves
+            .iter()
+            .zip(counts)
+            .map(|(mv, count)| (mv.to_uci(), count))
+            .collect::<Vec<_>>();
+
+        PerftResult {
+            nodes: divide.iter().map(|(_, count)| count).sum(),
+            divide,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+impl PerftResult {
+    /// Returns the amount of positions counted per second
+    pub fn nps(&self) -> u64 {
+        let seconds = self.elapsed.as_secs_f64();
+
+        if seconds == 0.0 {
+            return 0;
+        }
+
+        (self.nodes as f64 / seconds) as u64
+    }
+}
+
+impl Game {
+    /// Counts the positions at the end of every line of legal moves that is `depth` moves long
+    ///
+    /// Perft is the standard way to test a move generator, the counts for many positions are
+    /// known and any bug in the move generation shows up as a wrong count.
+    ///
+    /// # Arguments
+    /// * `depth` - The amount of half moves to play, a depth of 0 counts the current position
+    ///
+    /// # Returns
+    /// * `u64` - The amount of positions
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::Game;
+    ///
+    /// let game = Game::start_pos();
+    ///
+    /// assert_eq!(game.perft(1), 20);
+    /// assert_eq!(game.perft(3), 8902);
+    /// ```
+    pub fn perft(&self, depth: u32) -> u64 {
+        perft(&mut self.without_history(), depth)
+    }
+
+    /// Runs perft for each legal move in the current position, see `perft`
+    ///
+    /// When the count of a position doesn't match a reference engine the move with the wrong
+    /// count can be found by comparing the counts for each move, and then divided again.
+    ///
+    /// # Arguments
+    /// * `depth` - The amount of half moves to play, including the first move
+    ///
+    /// # Returns
+    /// * `Vec<(String, u64)>` - Each legal move in UCI notation together with the amount of
+    ///   positions after it, empty if the depth is 0
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::Game;
+    ///
+    /// let divide = Game::start_pos().perft_divide(2);
+    ///
+    /// assert_eq!(divide.len(), 20);
+    /// assert!(divide.contains(&("e2e4".to_string(), 20)));
+    /// ```
+    pub fn perft_divide(&self, depth: u32) -> Vec<(String, u64)> {
+        if depth == 0 {
+            return vec![];
+        }
+
+        let mut game = self.without_history();
+        let mut moves = MoveList::new();
+        game.generate_legal_into(&mut moves);
+
+        moves
+            .iter()
+            .map(|mv| {
+                let undo = game
+                    .make_move(*mv)
+                    .expect("generate_legal_into only returns valid moves");
+                let count = perft(&mut game, depth - 1);
+                game.unmake_move(*mv, undo);
+
+                (mv.to_uci(), count)
+            })
+            .collect()
+    }
+}
+
+/// Internal helper that counts the positions by making and unmaking moves
+pub(crate) fn perft(game: &mut Game, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let mut moves = MoveList::new();
+    game.generate_legal_into(&mut moves);
+
+    // Every generated move is legal, so the last ply doesn't have to be played
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    let mut count = 0;
+    for mv in &moves {
+        let undo = game
+            .make_move(*mv)
+            .expect("generate_legal_into only returns valid moves");
+        count += perft(game, depth - 1);
+        game.unmake_move(*mv, undo);
+    }
+
+    count
+}
+
+/// Internal helper that makes a move and counts the positions after it
+fn count_move(game: &mut Game, mv: Move, depth: u32, cache: &Option<PerftCache>) -> u64 {
+    let undo = game
+        .make_move(mv)
+        .expect("generate_legal_into only returns valid moves");
+    let count = match cache {
+        Some(cache) => perft_cached(game, depth - 1, cache),
+        None => perft(game, depth - 1),
+    };
+    game.unmake_move(mv, undo);
+
+    count
+}
+
+/// Internal helper that counts the positions like `perft` but looks them up in a cache first
+fn perft_cached(game: &mut Game, depth: u32, cache: &PerftCache) -> u64 {
+    // Counting the last ply is cheaper than a lookup
+    if depth <= 1 {
+        return perft(game, depth);
+    }
+
+    if let Some(count) = cache.get(game.hash_key(), depth) {
+        return count;
+    }
+
+    let mut moves = MoveList::new();
+    game.generate_legal_into(&mut moves);
+
+    let mut count = 0;
+    for mv in &moves {
+        let undo = game
+            .make_move(*mv)
+            .expect("generate_legal_into only returns valid moves");
+        count += perft_cached(game, depth - 1, cache);
+        game.unmake_move(*mv, undo);
+    }
+
+    cache.insert(game.hash_key(), depth, count);
+    count
+}
+
+/// Internal cache of perft counts that can be shared between threads without locks
+///
+/// Each entry stores the count and depth packed together, and the hash key xor the packed data.
+/// A read checks that the two still match the key, so an entry that two threads wrote at the
+/// same time is never mistaken for a hit.
+struct PerftCache {
+    entries: Vec<[AtomicU64; 2]>,
+}
+
+impl PerftCache {
+    fn new(megabytes: usize) -> PerftCache {
+        let size = std::mem::size_of::<[AtomicU64; 2]>();
+        // The amount of entries is a power of two so a key can be masked to an index
+        let len = 1 << ((megabytes << 20) / size).max(1).ilog2();
+
+        PerftCache {
+            entries: (0..len)
+                .map(|_| [AtomicU64::new(0), AtomicU64::new(0)])
+                .collect(),
+        }
+    }
+
+    fn entry(&self, key: u64, depth: u32) -> &[AtomicU64; 2] {
+        // The same position is cached at many depths, so the depth is mixed into the index
+        let index = key ^ (depth as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+
+        &self.entries[index as usize & (self.entries.len() - 1)]
+    }
+
+    fn get(&self, key: u64, depth: u32) -> Option<u64> {
+        let [check, data] = self.entry(key, depth);
+        let data = data.load(Ordering::Relaxed);
+
+        if check.load(Ordering::Relaxed) ^ data != key || data & 0xff != depth as u64 {
+            return None;
+        }
+
+        Some(data >> 8)
+    }
+
+    fn insert(&self, key: u64, depth: u32, count: u64) {
+        let [check, data] = self.entry(key, depth);
+        let packed = count << 8 | depth as u64;
+
+        check.store(key ^ packed, Ordering::Relaxed);
+        data.store(packed, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{Board, Color, Game, Move};
+
+/// Identifies a position for the purpose of the repetition rules
+///
+/// Two positions are the same if the same pieces are on the same tiles, the same color is to move,
+/// the castling rights are the same and the same en passant captures are possible. Unlike the
+/// en passant of a `Game`, the en passant of a key is only set if the pawn can actually be
+/// captured with a legal move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PositionKey {
+    board: Board,
+    turn: Color,
+    castling: [bool; 4],
+    en_passant: Option<(usize, usize)>,
+}
+
+impl Game {
+    /// Returns the key that identifies the current position
+    ///
+    /// # Returns
+    /// * `PositionKey` - The key of the current position
+    pub fn position_key(&self) -> PositionKey {
+        self.key_with_en_passant(self.en_passant.filter(|_| self.can_capture_en_passant()))
+    }
+
+    /// Internal helper that returns the key of the current position, given its legal moves
+    fn position_key_with_moves(&self, moves: &[Move]) -> PositionKey {
+        let en_passant = self.en_passant.filter(|ep| {
+            moves
+                .iter()
+                .any(|mv| mv.capture() == Some(*ep) && mv.to() != *ep)
+        });
+
+        self.key_with_en_passant(en_passant)
+    }
+
+    /// Internal helper that returns the key of the current position
+    ///
+    /// # Arguments
+    /// * `en_passant` - The en passant pawn, if it can be captured with a legal move
+    fn key_with_en_passant(&self, en_passant: Option<(usize, usize)>) -> PositionKey {
+        PositionKey {
+            board: self.board,
+            turn: self.turn,
+            castling: [
+                self.white_kingside_castle,
+                self.white_queenside_castle,
+                self.black_kingside_castle,
+                self.black_queenside_castle,
+            ],
+            en_passant,
+        }
+    }
+
+    /// Returns if the current position has occurred at least three times
+    ///
+    /// When this is true, the player to move can claim a draw.
+    ///
+    /// # Returns
+    /// * `bool` - If the current position has been repeated three times
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetitions() >= 3
+    }
+
+    /// Returns if the current position has occurred at least five times
+    ///
+    /// Unlike threefold repetition this draw doesn't have to be claimed, the game is over.
+    ///
+    /// # Returns
+    /// * `bool` - If the current position has been repeated five times
+    pub fn is_fivefold_repetition(&self) -> bool {
+        self.repetitions() >= 5
+    }
+
+    /// Internal helper that counts how many times the current position has occurred
+    fn repetitions(&self) -> usize {
+        self.repetitions_of(self.position_key())
+    }
+
+    /// Internal helper like `repetitions`, for when the legal moves have already been generated
+    pub(crate) fn repetitions_with_moves(&self, moves: &[Move]) -> usize {
+        self.repetitions_of(self.position_key_with_moves(moves))
+    }
+
+    /// Internal helper that counts how many times a position has occurred, including now
+    ///
+    /// Only the positions since the last capture or pawn move are searched, since no position
+    /// before such a move can ever occur again.
+    fn repetitions_of(&self, key: PositionKey) -> usize {
+        1 + self
+            .position_keys
+            .iter()
+            .rev()
+            .take(self.halfmove_clock as usize)
+            .filter(|k| **k == key)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Moves the knights on g1 and g8 out and back again
+    fn shuffle_knights(game: &mut Game) {
+        for (from, to) in [
+            ((6, 7), (5, 5)),
+            ((6, 0), (5, 2)),
+            ((5, 5), (6, 7)),
+            ((5, 2), (6, 0)),
+        ] {
+            game.apply_move(Move::Quiet { from, to }).unwrap();
+        }
+    }
+
+    #[test]
+    fn should_detect_threefold_and_fivefold_repetition() {
+        let mut game = Game::start_pos();
+
+        shuffle_knights(&mut game);
+        assert!(!game.is_threefold_repetition());
+
+        shuffle_knights(&mut game);
+        assert!(game.is_threefold_repetition());
+        assert!(!game.is_fivefold_repetition());
+
+        shuffle_knights(&mut game);
+        shuffle_knights(&mut game);
+        assert!(game.is_fivefold_repetition());
+
+        game.undo_move();
+        assert!(!game.is_fivefold_repetition());
+    }
+
+    #[test]
+    fn uncapturable_en_passant_should_not_matter() {
+        let mut game = Game::start_pos();
+        game.apply_move(Move::DoublePawnPush {
+            from: (4, 6),
+            to: (4, 4),
+        })
+        .unwrap();
+
+        let key = game.position_key();
+
+        game.apply_move(Move::Quiet {
+            from: (6, 0),
+            to: (5, 2),
+        })
+        .unwrap();
+        game.apply_move(Move::Quiet {
+            from: (6, 7),
+            to: (5, 5),
+        })
+        .unwrap();
+        game.apply_move(Move::Quiet {
+            from: (5, 2),
+            to: (6, 0),
+        })
+        .unwrap();
+        game.apply_move(Move::Quiet {
+            from: (5, 5),
+            to: (6, 7),
+        })
+        .unwrap();
+
+        assert_eq!(game.position_key(), key);
+    }
+
+    #[test]
+    fn capturable_en_passant_should_matter() {
+        let with_ep =
+            Game::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3").unwrap();
+        let without_ep =
+            Game::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq -").unwrap();
+
+        assert_ne!(with_ep.position_key(), without_ep.position_key());
+    }
+
+    #[test]
+    fn position_key_should_match_the_legal_moves() {
+        for (fen, capturable) in [
+            (
+                "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+                true,
+            ),
+            // The capture would leave the king in check along the rank
+            ("8/8/8/8/k2pP2R/8/8/4K3 b - e3 0 1", false),
+            // The pawn that could capture is pinned
+            ("3k4/8/8/8/3pP3/8/8/3RK3 b - e3 0 1", false),
+            // The capture would open a diagonal to the king
+            ("8/8/2k5/8/3pP3/8/6B1/4K3 b - e3 0 1", false),
+            // Without a king every capture is legal
+            ("8/8/8/8/3pP3/8/8/4K3 b - e3 0 1", true),
+        ] {
+            let game = Game::from_fen(fen).unwrap();
+            let moves = game.gen_all_moves().unwrap();
+
+            assert_eq!(game.can_capture_en_passant(), capturable, "{}", fen);
+            assert_eq!(
+                game.position_key(),
+                game.position_key_with_moves(&moves),
+                "{}",
+                fen
+            );
+        }
+    }
+}
@@ -0,0 +1,500 @@
+use crate::{error::ParseSanError, Color, File, Game, Move, Piece, PieceType, Rank, Square};
+
+impl Game {
+    /// Returns a move in Standard Algebraic Notation, e.g `Nbd7`, `exd6`, `O-O-O` or `e8=Q+`
+    ///
+    /// The move is expected to be a legal move for the current turn, like the ones returned by
+    /// `gen_all_moves`. En passant captures are written like any other pawn capture, as the PGN
+    /// standard requires, use `san_with_en_passant` to mark them.
+    ///
+    /// # Arguments
+    /// * `mv` - The move to render
+    ///
+    /// # Returns
+    /// * `String` - The move in SAN, including the `+` or `#` suffix if the move gives check
+    ///
+    /// # Panics
+    /// If there is no piece on the from tile of the move
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, Move};
+    ///
+    /// let game = Game::start_pos();
+    ///
+    /// assert_eq!(game.san(Move::Quiet { from: (6, 7), to: (5, 5) }), "Nf3");
+    /// ```
+    pub fn san(&self, mv: Move) -> String {
+        self.san_with_marker(mv, false)
+    }
+
+    /// Returns a move in SAN like `san`, but with en passant captures marked, e.g `exd6 e.p.`
+    ///
+    /// The marker isn't part of the PGN standard, but some books and older notations use it.
+    /// `parse_san` accepts moves rendered with the marker.
+    ///
+    /// # Arguments
+    /// * `mv` - The move to render
+    ///
+    /// # Returns
+    /// * `String` - The move in SAN, with ` e.p.` added to en passant captures after the `+` or
+    ///   `#` suffix, e.g `dxe3+ e.p.`
+    ///
+    /// # Panics
+    /// If there is no piece on the from tile of the move
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, Move};
+    ///
+    /// let game = Game::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+    /// let mv = Move::Capture { from: (4, 3), to: (3, 2), capture: (3, 3) };
+    ///
+    /// assert_eq!(game.san(mv), "exd6");
+    /// assert_eq!(game.san_with_en_passant(mv), "exd6 e.p.");
+    /// ```
+    pub fn san_with_en_passant(&self, mv: Move) -> String {
+        self.san_with_marker(mv, true)
+    }
+
+    /// Internal helper that renders a move in SAN, with or without the en passant marker
+    fn san_with_marker(&self, mv: Move, en_passant_marker: bool) -> String {
+        let mut san = self.san_without_suffix(mv);
+
+        let mut game = self.without_history();
+        if game.play_move(mv).is_ok() {
+            if game.is_checkmate() {
+                san.push('#');
+            } else if game.is_check() {
+                san.push('+');
+            }
+        }
+
+        if en_passant_marker && mv.capture().is_some_and(|capture| capture != mv.to()) {
+            san.push_str(" e.p.");
+        }
+
+        san
+    }
+
+    /// Parses a move in Standard Algebraic Notation into a legal move for the current turn
+    ///
+    /// Some common variations are accepted as well, e.g `0-0` for castling, a missing or extra
+    /// `+`, promotions without the `=` like `e8Q` and en passant markers like `exd6 e.p.`.
+    ///
+    /// # Arguments
+    /// * `san` - The move in SAN
+    ///
+    /// # Returns
+    /// * `Result<Move, ParseSanError>` - The legal move, or an error if the SAN is malformed,
+    ///   doesn't match a legal move or matches more than one legal move
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, Move};
+    ///
+    /// let game = Game::start_pos();
+    ///
+    /// assert_eq!(
+    ///     game.parse_san("Nf3").unwrap(),
+    ///     Move::Quiet { from: (6, 7), to: (5, 5) }
+    /// );
+    /// ```
+    pub fn parse_san(&self, san: &str) -> Result<Move, ParseSanError> {
+        let san = san.trim().trim_end_matches(['+', '#', '!', '?']).trim_end();
+        let san = san
+            .strip_suffix("e.p.")
+            .or_else(|| san.strip_suffix("ep"))
+            .unwrap_or(san)
+            .trim_end();
+        // The en passant marker can come both before and after the check suffix
+        let san = san.trim_end_matches(['+', '#']);
+
+        let moves = self.gen_all_moves().unwrap_or_default();
+
+        let castle = san.replace('0', "O");
+        let candidates = if castle == "O-O" {
+            moves
+                .into_iter()
+                .filter(|m| m.is_king_side_castle())
+                .collect::<Vec<Move>>()
+        } else if castle == "O-O-O" {
+            moves
+                .into_iter()
+                .filter(|m| m.is_queen_side_castle())
+                .collect::<Vec<Move>>()
+        } else {
+            let pattern = SanPattern::parse(san).ok_or(ParseSanError::Malformed)?;
+
+            moves
+                .into_iter()
+                .filter(|m| pattern.matches(self, *m))
+                .collect::<Vec<Move>>()
+        };
+
+        match candidates.as_slice() {
+            [] => Err(ParseSanError::IllegalMove),
+            [mv] => Ok(*mv),
+            _ => Err(ParseSanError::AmbiguousMove),
+        }
+    }
+
+    /// Internal helper that returns a move in SAN without the check or checkmate suffix
+    ///
+    /// # Panics
+    /// If there is no piece on the from tile of the move
+    pub(crate) fn san_without_suffix(&self, mv: Move) -> String {
+        if mv.is_king_side_castle() {
+            return "O-O".to_string();
+        }
+
+        if mv.is_queen_side_castle() {
+            return "O-O-O".to_string();
+        }
+
+        let (from_x, from_y) = mv.from();
+        let piece_type = self
+            .board
+            .get_tile(from_x, from_y)
+            .map(|p| p.piece_type)
+            .expect("There has to be a piece on the from tile of the move");
+
+        let mut san = String::new();
+
+        if piece_type == PieceType::Pawn {
+            if mv.is_capture() {
+                san.push(mv.from_square().file().to_char());
+            }
+        } else {
+            san.push(
+                Piece {
+                    piece_type,
+                    color: Color::White,
+                }
+                .into(),
+            );
+            san.push_str(&self.disambiguation(mv, piece_type));
+        }
+
+        if mv.is_capture() {
+            san.push('x');
+        }
+
+        san.push_str(&mv.to_square().to_string());
+
+        if let Some(promotion) = mv.promotion() {
+            san.push('=');
+            san.push(
+                Piece {
+                    piece_type: promotion,
+                    color: Color::White,
+                }
+                .into(),
+            );
+        }
+
+        san
+    }
+
+    /// Internal helper that returns what's needed to tell a piece move apart from moves by other
+    /// pieces of the same type to the same tile. Prefers the file, then the rank and lastly both.
+    fn disambiguation(&self, mv: Move, piece_type: PieceType) -> String {
+        let from = mv.from();
+
+        let others = self
+            .gen_all_moves()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|m| m.to() == mv.to() && m.from() != from)
+            .filter(|m| {
+                self.board
+                    .get_tile(m.from().0, m.from().1)
+                    .is_some_and(|p| p.piece_type == piece_type)
+            })
+            .map(|m| m.from())
+            .collect::<Vec<(usize, usize)>>();
+
+        let name = mv.from_square().to_string();
+
+        if others.is_empty() {
+            String::new()
+        } else if others.iter().all(|(x, _)| *x != from.0) {
+            name[..1].to_string()
+        } else if others.iter().all(|(_, y)| *y != from.1) {
+            name[1..].to_string()
+        } else {
+            name
+        }
+    }
+}
+
+/// Internal struct that holds the parts of a non castling SAN move
+struct SanPattern {
+    piece_type: PieceType,
+    from_x: Option<usize>,
+    from_y: Option<usize>,
+    to: (usize, usize),
+    promotion: Option<PieceType>,
+}
+
+impl SanPattern {
+    /// Splits a SAN move into its parts, returns None if the move is malformed
+    fn parse(san: &str) -> Option<SanPattern> {
+        let mut chars = san.chars().collect::<Vec<char>>();
+
+        // Pawn moves are the only moves without a piece letter
+        let piece_type = match chars.first()? {
+            'N' => PieceType::Knight,
+            'B' => PieceType::Bishop,
+            'R' => PieceType::Rook,
+            'Q' => PieceType::Queen,
+            'K' => PieceType::King,
+            _ => PieceType::Pawn,
+        };
+        if piece_type != PieceType::Pawn {
+            chars.remove(0);
+        }
+
+        // A promotion is the only thing that can come after the to tile
+        let promotion = match chars.last()?.to_ascii_uppercase() {
+            'N' => Some(PieceType::Knight),
+            'B' => Some(PieceType::Bishop),
+            'R' => Some(PieceType::Rook),
+            'Q' => Some(PieceType::Queen),
+            _ => None,
+        };
+        if promotion.is_some() {
+            if piece_type != PieceType::Pawn {
+                return None;
+            }
+
+            chars.pop();
+            if chars.last() == Some(&'=') {
+                chars.pop();
+            }
+        }
+
+        if chars.len() < 2 {
+            return None;
+        }
+
+        let to_rank = chars.pop()?;
+        let to_file = chars.pop()?;
+        let to = Square::new(File::from_char(to_file)?, Rank::from_char(to_rank)?).coords();
+
+        if matches!(chars.last(), Some('x') | Some(':')) {
+            chars.pop();
+        }
+
+        let (from_x, from_y) = match chars.as_slice() {
+            [] => (None, None),
+            [c] if File::from_char(*c).is_some() => (File::from_char(*c).map(File::index), None),
+            [c] if Rank::from_char(*c).is_some() => (None, Rank::from_char(*c).map(Rank::y)),
+            [file, rank] => (
+                Some(File::from_char(*file)?.index()),
+                Some(Rank::from_char(*rank)?.y()),
+            ),
+            _ => return None,
+        };
+
+        Some(SanPattern {
+            piece_type,
+            from_x,
+            from_y,
+            to,
+            promotion,
+        })
+    }
+
+    /// Returns if a move in a game fits the pattern
+    fn matches(&self, game: &Game, mv: Move) -> bool {
+        let (x, y) = mv.from();
+
+        !mv.is_castle()
+            && mv.to() == self.to
+            && mv.promotion() == self.promotion
+            && self.from_x.is_none_or(|from_x| from_x == x)
+            && self.from_y.is_none_or(|from_y| from_y == y)
+            && game
+                .board
+                .get_tile(x, y)
+                .is_some_and(|p| p.piece_type == self.piece_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_render_san() {
+        let cases = [
+            (
+                "rn1qkb1r/ppp1pppp/5n2/3p4/3P4/5N2/PPP1PPPP/RNBQKB1R b KQkq - 0 1",
+                Move::Quiet {
+                    from: (1, 0),
+                    to: (3, 1),
+                },
+                "Nbd7",
+            ),
+            (
+                "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3",
+                Move::Capture {
+                    from: (4, 3),
+                    to: (3, 2),
+                    capture: (3, 3),
+                },
+                "exd6",
+            ),
+            (
+                "r3k3/8/8/8/8/8/8/4K3 b q - 0 1",
+                Move::Castle {
+                    from: (4, 0),
+                    to: (2, 0),
+                    rook_from: (0, 0),
+                    rook_to: (3, 0),
+                },
+                "O-O-O",
+            ),
+            (
+                "8/4P3/8/8/k7/8/8/4K3 w - - 0 1",
+                Move::QuietPromotion {
+                    from: (4, 1),
+                    to: (4, 0),
+                    promotion: PieceType::Queen,
+                },
+                "e8=Q+",
+            ),
+            (
+                "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1",
+                Move::Quiet {
+                    from: (0, 7),
+                    to: (0, 5),
+                },
+                "R1a3",
+            ),
+            (
+                "8/8/k7/8/4Q2Q/8/8/K6Q w - - 0 1",
+                Move::Quiet {
+                    from: (7, 4),
+                    to: (4, 7),
+                },
+                "Qh4e1",
+            ),
+            (
+                "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2",
+                Move::Quiet {
+                    from: (3, 0),
+                    to: (7, 4),
+                },
+                "Qh4#",
+            ),
+            (
+                "4k3/8/8/8/8/8/8/2R1K2R w K - 0 1",
+                Move::Quiet {
+                    from: (2, 7),
+                    to: (2, 0),
+                },
+                "Rc8+",
+            ),
+        ];
+
+        for (fen, mv, san) in cases {
+            let game = Game::from_fen(fen).unwrap();
+            assert_eq!(game.san(mv), san, "{}", fen);
+            assert_eq!(game.parse_san(san).unwrap(), mv, "{}", fen);
+        }
+    }
+
+    #[test]
+    fn should_mark_en_passant() {
+        let game = Game::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3")
+            .unwrap();
+        let en_passant = Move::Capture {
+            from: (4, 3),
+            to: (3, 2),
+            capture: (3, 3),
+        };
+        let push = Move::Quiet {
+            from: (4, 3),
+            to: (4, 2),
+        };
+
+        assert_eq!(game.san_with_en_passant(en_passant), "exd6 e.p.");
+        assert_eq!(game.san_with_en_passant(push), "e6");
+        assert_eq!(game.parse_san("exd6 e.p.").unwrap(), en_passant);
+
+        // The marker goes after the check suffix
+        let game = Game::from_fen("8/8/8/2k5/3pP3/8/5K2/8 b - e3 0 1").unwrap();
+        let en_passant = Move::Capture {
+            from: (3, 4),
+            to: (4, 5),
+            capture: (4, 4),
+        };
+        assert_eq!(game.san_with_en_passant(en_passant), "dxe3+ e.p.");
+        assert_eq!(game.parse_san("dxe3+ e.p.").unwrap(), en_passant);
+        assert_eq!(game.parse_san("dxe3 e.p.+").unwrap(), en_passant);
+    }
+
+    #[test]
+    fn should_parse_san_variants() {
+        let game = Game::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1").unwrap();
+
+        let castle = Move::Castle {
+            from: (4, 7),
+            to: (6, 7),
+            rook_from: (7, 7),
+            rook_to: (5, 7),
+        };
+        assert_eq!(game.parse_san("O-O").unwrap(), castle);
+        assert_eq!(game.parse_san("0-0").unwrap(), castle);
+        assert!(game.parse_san("0-0-0").unwrap().is_queen_side_castle());
+
+        let en_passant = Move::Capture {
+            from: (4, 3),
+            to: (3, 2),
+            capture: (3, 3),
+        };
+        assert_eq!(game.parse_san("exd6").unwrap(), en_passant);
+        assert_eq!(game.parse_san("exd6ep").unwrap(), en_passant);
+        assert_eq!(game.parse_san("exd6 e.p.").unwrap(), en_passant);
+
+        let promotion = Move::CapturePromotion {
+            from: (1, 1),
+            to: (0, 0),
+            capture: (0, 0),
+            promotion: PieceType::Queen,
+        };
+        assert_eq!(game.parse_san("bxa8=Q").unwrap(), promotion);
+        assert_eq!(game.parse_san("bxa8Q+").unwrap(), promotion);
+        assert_eq!(game.parse_san("bxa8=Q!?").unwrap(), promotion);
+        assert_eq!(game.parse_san("Rxa8").unwrap().from(), (0, 7));
+    }
+
+    #[test]
+    fn should_return_san_errors() {
+        let game = Game::from_fen("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1").unwrap();
+
+        assert!(matches!(
+            game.parse_san("Ra3"),
+            Err(ParseSanError::AmbiguousMove)
+        ));
+        assert!(matches!(
+            game.parse_san("Rb3"),
+            Err(ParseSanError::IllegalMove)
+        ));
+        assert!(matches!(
+            game.parse_san("O-O"),
+            Err(ParseSanError::IllegalMove)
+        ));
+        assert!(matches!(
+            game.parse_san("Ra9"),
+            Err(ParseSanError::Malformed)
+        ));
+        assert!(matches!(
+            game.parse_san("Zz"),
+            Err(ParseSanError::Malformed)
+        ));
+        assert!(matches!(game.parse_san(""), Err(ParseSanError::Malformed)));
+    }
+}
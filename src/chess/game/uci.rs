@@ -0,0 +1,148 @@
+use crate::{error::ParseUciError, File, Game, Move, PieceType, Rank, Square};
+
+impl Game {
+    /// Parses a move in UCI long algebraic notation into a legal move for the current turn
+    ///
+    /// The move is mapped to the right kind of `Move`, e.g `e1g1` becomes a `Move::Castle` if the
+    /// king is on e1 and `e2e4` a `Move::DoublePawnPush`.
+    ///
+    /// # Arguments
+    /// * `uci` - The move in UCI notation, e.g `e2e4` or `e7e8q`
+    ///
+    /// # Returns
+    /// * `Result<Move, ParseUciError>` - The legal move, or an error if the string is malformed
+    ///   or isn't a legal move
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, Move};
+    ///
+    /// let game = Game::start_pos();
+    ///
+    /// assert_eq!(
+    ///     game.parse_uci_move("e2e4").unwrap(),
+    ///     Move::DoublePawnPush { from: (4, 6), to: (4, 4) }
+    /// );
+    /// ```
+    pub fn parse_uci_move(&self, uci: &str) -> Result<Move, ParseUciError> {
+        let chars = uci.trim().chars().collect::<Vec<char>>();
+
+        if chars.len() != 4 && chars.len() != 5 {
+            return Err(ParseUciError::Malformed);
+        }
+
+        let tile = |file: char, rank: char| {
+            File::from_char(file)
+                .zip(Rank::from_char(rank))
+                .map(|(file, rank)| Square::new(file, rank))
+                .ok_or(ParseUciError::Malformed)
+        };
+
+        let from = tile(chars[0], chars[1])?;
+        let to = tile(chars[2], chars[3])?;
+
+        let promotion = match chars.get(4).map(|c| c.to_ascii_lowercase()) {
+            None => None,
+            Some('q') => Some(PieceType::Queen),
+            Some('r') => Some(PieceType::Rook),
+            Some('b') => Some(PieceType::Bishop),
+            Some('n') => Some(PieceType::Knight),
+            Some(_) => return Err(ParseUciError::Malformed),
+        };
+
+        self.get_move(from, to)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|mv| mv.promotion() == promotion)
+            .ok_or(ParseUciError::IllegalMove)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uci_should_round_trip() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        ];
+
+        for fen in fens {
+            let game = Game::from_fen(fen).unwrap();
+
+            for mv in game.gen_all_moves().unwrap() {
+                assert_eq!(game.parse_uci_move(&mv.to_uci()).unwrap(), mv);
+            }
+        }
+    }
+
+    #[test]
+    fn try_to_uci_should_reject_moves_outside_the_board() {
+        let mv = Move::Quiet {
+            from: (4, 6),
+            to: (4, 8),
+        };
+
+        assert_eq!(mv.try_to_uci(), None);
+        assert_eq!(
+            Move::Quiet {
+                from: (4, 6),
+                to: (4, 5),
+            }
+            .try_to_uci(),
+            Some("e2e3".to_string())
+        );
+    }
+
+    #[test]
+    fn should_map_to_the_right_move() {
+        let game = Game::from_fen("r3k2r/1P6/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1").unwrap();
+
+        assert_eq!(
+            game.parse_uci_move("e1g1").unwrap(),
+            Move::Castle {
+                from: (4, 7),
+                to: (6, 7),
+                rook_from: (7, 7),
+                rook_to: (5, 7),
+            }
+        );
+        assert_eq!(
+            game.parse_uci_move("e5d6").unwrap(),
+            Move::Capture {
+                from: (4, 3),
+                to: (3, 2),
+                capture: (3, 3),
+            }
+        );
+        assert_eq!(
+            game.parse_uci_move("b7a8n").unwrap().promotion(),
+            Some(PieceType::Knight)
+        );
+        assert_eq!(game.parse_uci_move("b7a8n").unwrap().to_uci(), "b7a8n");
+
+        assert!(matches!(
+            game.parse_uci_move("b7a8"),
+            Err(ParseUciError::IllegalMove)
+        ));
+        assert!(matches!(
+            game.parse_uci_move("e1e3"),
+            Err(ParseUciError::IllegalMove)
+        ));
+        assert!(matches!(
+            game.parse_uci_move("e1g9"),
+            Err(ParseUciError::Malformed)
+        ));
+        assert!(matches!(
+            game.parse_uci_move("b7a8k"),
+            Err(ParseUciError::Malformed)
+        ));
+        assert!(matches!(
+            game.parse_uci_move("0000"),
+            Err(ParseUciError::Malformed)
+        ));
+    }
+}
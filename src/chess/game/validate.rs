@@ -0,0 +1,222 @@
+use crate::{
+    error::{PositionError, StrictFromFenError},
+    Color, Game, PieceType, Rank, Square,
+};
+
+impl Game {
+    /// Checks that the position could have been reached in a game
+    ///
+    /// The checks are that each color has exactly one king, that no pawns are on the first or last
+    /// rank, that the color that isn't to move isn't in check, that the castling rights and en
+    /// passant match the pieces on the board and that each color doesn't have more promoted pieces
+    /// than it has lost pawns. Passing the checks doesn't guarantee that the position is
+    /// reachable, but every position that fails them is unreachable.
+    ///
+    /// # Returns
+    /// * `Vec<PositionError>` - Every problem with the position, empty if the position is valid
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{error::PositionError, Color, Game};
+    ///
+    /// assert!(Game::start_pos().validate().is_empty());
+    ///
+    /// let game = Game::from_fen("4k3/8/8/8/8/8/8/8 w - - 0 1").unwrap();
+    /// assert_eq!(game.validate(), vec![PositionError::MissingKing(Color::White)]);
+    /// ```
+    pub fn validate(&self) -> Vec<PositionError> {
+        let mut errors = vec![];
+
+        for color in [Color::White, Color::Black] {
+            match self.board.by_piece(color, PieceType::King).count() {
+                0 => errors.push(PositionError::MissingKing(color)),
+                1 => (),
+                _ => errors.push(PositionError::TooManyKings(color)),
+            }
+        }
+
+        for (square, piece) in self.board.iter() {
+            let back_rank = square.rank() == Rank::FIRST || square.rank() == Rank::EIGHTH;
+
+            if piece.piece_type == PieceType::Pawn && back_rank {
+                errors.push(PositionError::PawnOnBackRank(square));
+            }
+        }
+
+        for color in [Color::White, Color::Black] {
+            let count = |piece_type| self.board.by_piece(color, piece_type).count();
+            let pawns = count(PieceType::Pawn);
+            let promoted = count(PieceType::Queen).saturating_sub(1)
+                + count(PieceType::Rook).saturating_sub(2)
+                + count(PieceType::Bishop).saturating_sub(2)
+                + count(PieceType::Knight).saturating_sub(2);
+
+            if pawns > 8 {
+                errors.push(PositionError::TooManyPawns(color));
+            } else if pawns + promoted > 8 {
+                errors.push(PositionError::TooManyPromotedPieces(color));
+            }
+        }
+
+        if self.can_capture_king(self.turn) {
+            errors.push(PositionError::OpponentInCheck);
+        }
+
+        errors.extend(self.castling_errors());
+
+        if let Some(error) = self.en_passant_error() {
+            errors.push(error);
+        }
+
+        errors
+    }
+
+    /// Creates a new game from a FEN string and checks that the position is valid
+    ///
+    /// This is a stricter version of `from_fen`, which accepts any position as long as the FEN
+    /// string can be parsed. See `validate` for what's checked.
+    ///
+    /// # Returns
+    /// * `Result<Game, StrictFromFenError>` - The game, or an error if the FEN string can't be
+    ///   parsed or the position is invalid
+    pub fn from_fen_strict(fen: &str) -> Result<Game, StrictFromFenError> {
+        let game = Game::from_fen(fen)?;
+        let errors = game.validate();
+
+        if !errors.is_empty() {
+            return Err(StrictFromFenError::InvalidPosition(errors));
+        }
+
+        Ok(game)
+    }
+
+    /// Internal helper that returns the castling rights that don't have a king and rook at home
+    fn castling_errors(&self) -> Vec<PositionError> {
+        let rights = [
+            (self.white_kingside_castle, 'K', Square::E1, Square::H1),
+            (self.white_queenside_castle, 'Q', Square::E1, Square::A1),
+            (self.black_kingside_castle, 'k', Square::E8, Square::H8),
+            (self.black_queenside_castle, 'q', Square::E8, Square::A8),
+        ];
+
+        rights
+            .into_iter()
+            .filter(|(right, ..)| *right)
+            .filter(|(_, right, king, rook)| {
+                let color = if right.is_ascii_uppercase() {
+                    Color::White
+                } else {
+                    Color::Black
+                };
+                let is = |square, piece_type| {
+                    self.board
+                        .piece_at(square)
+                        .is_some_and(|p| p.color == color && p.piece_type == piece_type)
+                };
+
+                !is(*king, PieceType::King) || !is(*rook, PieceType::Rook)
+            })
+            .map(|(_, right, ..)| PositionError::ImpossibleCastlingRight(right))
+            .collect()
+    }
+
+    /// Internal helper that checks that the pawn that can be captured en passant could just have
+    /// made a double pawn push
+    fn en_passant_error(&self) -> Option<PositionError> {
+        let (x, y) = self.en_passant?;
+        let pawn = Square::from_coords(x, y)?;
+
+        // The rank the pawn moved from and the rank it passed over
+        let (start_rank, passed_rank, pawn_rank) = match self.turn {
+            Color::White => (Rank::SEVENTH, Rank::SIXTH, Rank::FIFTH),
+            Color::Black => (Rank::SECOND, Rank::THIRD, Rank::FOURTH),
+        };
+
+        let is_pawn = self
+            .board
+            .piece_at(pawn)
+            .is_some_and(|p| p.piece_type == PieceType::Pawn && p.color == self.turn.opposite());
+        let is_empty = |rank| {
+            self.board
+                .piece_at(Square::new(pawn.file(), rank))
+                .is_none()
+        };
+
+        if pawn.rank() != pawn_rank || !is_pawn || !is_empty(start_rank) || !is_empty(passed_rank) {
+            return Some(PositionError::ImpossibleEnPassant(pawn));
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(fen: &str) -> Vec<PositionError> {
+        Game::from_fen(fen).unwrap().validate()
+    }
+
+    #[test]
+    fn valid_positions_should_pass() {
+        assert!(errors("rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq c3 0 1").is_empty());
+        assert!(
+            errors("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1").is_empty()
+        );
+    }
+
+    #[test]
+    fn invalid_positions_should_be_reported() {
+        assert_eq!(
+            errors("k1K1K3/8/8/8/8/8/8/8 w - - 0 1"),
+            vec![PositionError::TooManyKings(Color::White)]
+        );
+        assert_eq!(
+            errors("P3k3/8/8/8/8/8/8/4K2p w - - 0 1"),
+            vec![
+                PositionError::PawnOnBackRank(Square::H1),
+                PositionError::PawnOnBackRank(Square::A8)
+            ]
+        );
+        assert_eq!(
+            errors("4k3/4R3/8/8/8/8/8/4K3 w - - 0 1"),
+            vec![PositionError::OpponentInCheck]
+        );
+        assert_eq!(
+            errors("r3k3/8/8/8/8/8/8/4K2R w KQkq - 0 1"),
+            vec![
+                PositionError::ImpossibleCastlingRight('Q'),
+                PositionError::ImpossibleCastlingRight('k')
+            ]
+        );
+        assert_eq!(
+            errors("4k3/8/8/4pP2/8/8/8/4K3 w - e6 0 1"),
+            Vec::<PositionError>::new()
+        );
+        assert_eq!(
+            errors("4k3/4b3/8/4pP2/8/8/8/4K3 w - e6 0 1"),
+            vec![PositionError::ImpossibleEnPassant(Square::E5)]
+        );
+        assert_eq!(
+            errors("4k3/8/8/8/8/QQQ5/PPPPPPPQ/QQQ1K3 w - - 0 1"),
+            vec![PositionError::TooManyPromotedPieces(Color::White)]
+        );
+    }
+
+    #[test]
+    fn strict_from_fen_should_reject_invalid_positions() {
+        assert!(
+            Game::from_fen_strict("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
+                .is_ok()
+        );
+        assert!(matches!(
+            Game::from_fen_strict("8/8/8/8/8/8/8/8 w - - 0 1"),
+            Err(StrictFromFenError::InvalidPosition(errors)) if errors.len() == 2
+        ));
+        assert!(matches!(
+            Game::from_fen_strict("8/8/8/8/8/8/8 w - - 0 1"),
+            Err(StrictFromFenError::InvalidFen(_))
+        ));
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{Bitboard, Color, Game, Piece, PieceType, Square};
+
+/// The random numbers that are combined into a hash key, one for each thing a position can have
+struct ZobristKeys {
+    /// One number for each color, piece type and square
+    pieces: [[[u64; 64]; 6]; 2],
+    /// One number for each castling right, in the order `K`, `Q`, `k` and `q`
+    castling: [u64; 4],
+    /// One number for each file a pawn can be captured en passant on
+    en_passant: [u64; 8],
+    /// Included when it's black to move
+    black_to_move: u64,
+}
+
+/// The numbers are generated at compile time from a fixed seed, so keys are the same between runs
+/// and versions of the library
+const KEYS: ZobristKeys = ZobristKeys::generate(0x5eed_c0ff_ee15_900d);
+
+impl ZobristKeys {
+    const fn generate(seed: u64) -> ZobristKeys {
+        let mut state = seed;
+        let mut keys = ZobristKeys {
+            pieces: [[[0; 64]; 6]; 2],
+            castling: [0; 4],
+            en_passant: [0; 8],
+            black_to_move: 0,
+        };
+
+        let mut i = 0;
+        while i < 2 * 6 * 64 {
+            keys.pieces[i / 384][i / 64 % 6][i % 64] = split_mix(&mut state);
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i < 4 {
+            keys.castling[i] = split_mix(&mut state);
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i < 8 {
+            keys.en_passant[i] = split_mix(&mut state);
+            i += 1;
+        }
+
+        keys.black_to_move = split_mix(&mut state);
+        keys
+    }
+}
+
+/// Internal splitmix64 random number generator, it spreads the bits of consecutive states well
+const fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Internal helper that returns the number for a piece on a square
+fn piece_key(piece: Piece, square: Square) -> u64 {
+    KEYS.pieces[piece.color as usize][piece.piece_type as usize][square.index()]
+}
+
+impl Game {
+    /// Returns the Zobrist hash key of the current position
+    ///
+    /// The key covers the pieces, the color to move, the castling rights and the file of the pawn
+    /// that can be captured en passant. The en passant file is only included if a pawn of the
+    /// color to move stands next to the pawn, so positions that only differ by an en passant
+    /// capture that can't happen get the same key. The clocks and the history aren't included.
+    ///
+    /// The key is kept up to date as moves are applied and taken back, so this is free to call.
+    ///
+    /// # Returns
+    /// * `u64` - The hash key, equal positions always have equal keys
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, Square};
+    ///
+    /// let play = |moves: [(Square, Square); 3]| {
+    ///     let mut game = Game::start_pos();
+    ///     for (from, to) in moves {
+    ///         game.apply_move(game.get_move(from, to).unwrap()[0]).unwrap();
+    ///     }
+    ///     game
+    /// };
+    ///
+    /// let nf3 = (Square::G1, Square::F3);
+    /// let nf6 = (Square::G8, Square::F6);
+    /// let nc3 = (Square::B1, Square::C3);
+    /// let a = play([nf3, nf6, nc3]);
+    /// let b = play([nc3, nf6, nf3]);
+    ///
+    /// assert_eq!(a.hash_key(), b.hash_key());
+    /// assert_ne!(a.hash_key(), Game::start_pos().hash_key());
+    /// ```
+    pub fn hash_key(&self) -> u64 {
+        self.hash
+    }
+
+    /// Internal helper that computes the hash key from scratch, used when a position is set up
+    pub(crate) fn compute_hash(&self) -> u64 {
+        self.board
+            .iter()
+            .fold(self.state_hash(), |hash, (square, piece)| {
+                hash ^ piece_key(piece, square)
+            })
+    }
+
+    /// Internal helper that returns the part of the hash key that isn't the pieces
+    ///
+    /// Moves change these in many ways, so the part is removed from the key before a move and
+    /// added back after it instead of updating it piece by piece.
+    pub(crate) fn state_hash(&self) -> u64 {
+        let castling = [
+            self.white_kingside_castle,
+            self.white_queenside_castle,
+            self.black_kingside_castle,
+            self.black_queenside_castle,
+        ];
+
+        let mut hash = castling
+            .into_iter()
+            .zip(KEYS.castling)
+            .filter(|(right, _)| *right)
+            .fold(0, |hash, (_, key)| hash ^ key);
+
+        let en_passant = self
+            .en_passant
+            .and_then(|(x, y)| Square::from_coords(x, y))
+            .filter(|pawn| {
+                let beside = Bitboard::from(*pawn).east() | Bitboard::from(*pawn).west();
+
+                !(beside & self.board.by_piece(self.turn, PieceType::Pawn)).is_empty()
+            });
+
+        if let Some(pawn) = en_passant {
+            hash ^= KEYS.en_passant[pawn.file().index()];
+        }
+        if self.turn == Color::Black {
+            hash ^= KEYS.black_to_move;
+        }
+
+        hash
+    }
+
+    /// Internal helper that sets or clears a tile and updates the hash key
+    pub(crate) fn put_piece(&mut self, x: usize, y: usize, piece: Option<Piece>) {
+        let square = Square::from_coords(x, y).expect("x and y must be between 0 and 7");
+
+        if let Some(previous) = self.board.piece_at(square) {
+            self.hash ^= piece_key(previous, square);
+            self.board.remove_tile(x, y);
+        }
+        if let Some(piece) = piece {
+            self.hash ^= piece_key(piece, square);
+            self.board.set_tile(x, y, piece);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Move, MoveList};
+
+    use super::*;
+
+    /// Plays every move a few plies deep and checks the kept key against one computed from scratch
+    fn check_keys(game: &mut Game, depth: u8) {
+        assert_eq!(game.hash_key(), game.compute_hash(), "{}", game.fen());
+
+        if depth == 0 {
+            return;
+        }
+
+        let key = game.hash_key();
+        let mut moves = MoveList::new();
+        game.generate_legal_into(&mut moves);
+
+        for mv in &moves {
+            let undo = game.make_move(*mv).unwrap();
+            check_keys(game, depth - 1);
+            game.unmake_move(*mv, undo);
+
+            assert_eq!(game.hash_key(), key);
+        }
+    }
+
+    #[test]
+    fn kept_key_should_match_computed_key() {
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ];
+
+        for fen in fens {
+            check_keys(&mut Game::from_fen(fen).unwrap(), 2);
+        }
+    }
+
+    #[test]
+    fn rejected_moves_should_not_change_the_key() {
+        let mut game = Game::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1").unwrap();
+        let before = game.clone();
+
+        let empty_square = Move::Quiet {
+            from: (0, 0),
+            to: (0, 1),
+        };
+        let missing_rook = Move::Castle {
+            from: (4, 7),
+            to: (6, 7),
+            rook_from: (7, 7),
+            rook_to: (5, 7),
+        };
+
+        for mv in [empty_square, missing_rook] {
+            assert!(game.apply_move(mv).is_err());
+            assert_eq!(game.hash_key(), game.compute_hash());
+            assert_eq!(game, before);
+            assert_eq!(game.fen(), before.fen());
+        }
+    }
+
+    #[test]
+    fn key_should_only_include_possible_en_passant() {
+        let key = |fen| Game::from_fen(fen).unwrap().hash_key();
+
+        // No black pawn can capture the e-pawn
+        assert_eq!(
+            key("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1"),
+            key("4k3/8/8/8/4P3/8/8/4K3 b - - 0 1")
+        );
+        assert_ne!(
+            key("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1"),
+            key("4k3/8/8/8/3pP3/8/8/4K3 b - - 0 1")
+        );
+        assert_ne!(
+            key("4k3/8/8/8/8/8/8/4K2R w K - 0 1"),
+            key("4k3/8/8/8/8/8/8/4K2R w - - 0 1")
+        );
+        assert_ne!(
+            key("4k3/8/8/8/8/8/8/4K2R w - - 0 1"),
+            key("4k3/8/8/8/8/8/8/4K2R b - - 0 1")
+        );
+    }
+}
@@ -0,0 +1,445 @@
+use std::collections::BTreeMap;
+
+use crate::{error::GameApplyMoveError, Game, Move, PgnResult};
+
+/// Identifies a node in a `GameTree`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// Internal struct that holds a position in the tree and the move that led to it
+#[derive(Debug, Clone)]
+struct Node {
+    parent: Option<NodeId>,
+    /// The move that led to this node, None for the root
+    mv: Option<Move>,
+    /// The position after the move, without any history
+    position: Game,
+    /// The continuations from this node, the first one is the main line
+    children: Vec<NodeId>,
+    /// A comment that comes before the move, only used when the node starts a variation
+    starting_comment: Option<String>,
+    /// A comment that comes after the move
+    comment: Option<String>,
+    /// Numeric annotation glyphs, e.g `1` for a good move `!`
+    nags: Vec<u8>,
+}
+
+/// A game with variations, comments and annotations
+///
+/// Every node in the tree is a position reached by applying a move to the position of its parent.
+/// The children of a node are ordered, the first child is the main line and the rest are
+/// variations. The root of the tree holds the starting position.
+///
+/// Nodes are referred to with `NodeId`s. An id of a node that has been deleted with
+/// `delete_variation` is no longer valid, and all functions panic if they get one.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{Game, GameTree};
+///
+/// let mut tree = GameTree::new(Game::start_pos());
+/// let root = tree.root();
+///
+/// let e4 = tree.add_san(root, "e4").unwrap();
+/// let d4 = tree.add_san(root, "d4").unwrap();
+/// tree.set_comment(d4, "Also good");
+///
+/// assert_eq!(tree.main_line(), vec![e4]);
+///
+/// tree.promote_variation(d4);
+/// assert_eq!(tree.main_line(), vec![d4]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameTree {
+    nodes: Vec<Option<Node>>,
+    pub(crate) tags: BTreeMap<String, String>,
+    pub(crate) result: PgnResult,
+}
+
+impl GameTree {
+    /// Creates a tree with only a root node
+    ///
+    /// The history of the game is dropped, the tree starts from its current position.
+    ///
+    /// # Arguments
+    /// * `game` - The position at the root of the tree
+    pub fn new(game: Game) -> GameTree {
+        GameTree {
+            nodes: vec![Some(Node {
+                parent: None,
+                mv: None,
+                position: game.without_history(),
+                children: vec![],
+                starting_comment: None,
+                comment: None,
+                nags: vec![],
+            })],
+            tags: BTreeMap::new(),
+            result: PgnResult::Ongoing,
+        }
+    }
+
+    /// Returns the root node, which holds the starting position
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    fn node(&self, id: NodeId) -> &Node {
+        self.nodes[id.0]
+            .as_ref()
+            .expect("The node has been deleted")
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        self.nodes[id.0]
+            .as_mut()
+            .expect("The node has been deleted")
+    }
+
+    /// Adds a move after a node
+    ///
+    /// If the move already has a node after `parent` that node is returned, otherwise a new node
+    /// is added as the last variation. The first move added after a node becomes the main line.
+    ///
+    /// # Arguments
+    /// * `parent` - The node to add the move after
+    /// * `mv` - The move, it has to be legal in the position of `parent`
+    ///
+    /// # Returns
+    /// * `Result<NodeId, GameApplyMoveError>` - The node after the move, or an error if the move
+    ///   isn't legal
+    pub fn add_move(&mut self, parent: NodeId, mv: Move) -> Result<NodeId, GameApplyMoveError> {
+        if let Some(existing) = self
+            .node(parent)
+            .children
+            .iter()
+            .find(|c| self.node(**c).mv == Some(mv))
+        {
+            return Ok(*existing);
+        }
+
+        self.add_new_move(parent, mv)
+    }
+
+    /// Internal helper that adds a move after a node as a new node, even if the move already has
+    /// a node after `parent`
+    ///
+    /// Used when reading PGN, where a variation can repeat the move it's a variation of.
+    ///
+    /// # Returns
+    /// * `Result<NodeId, GameApplyMoveError>` - The new node, or an error if the move isn't legal
+    pub(crate) fn add_new_move(
+        &mut self,
+        parent: NodeId,
+        mv: Move,
+    ) -> Result<NodeId, GameApplyMoveError> {
+        let mut position = self.node(parent).position.without_history();
+
+        if !position.gen_all_moves().unwrap_or_default().contains(&mv) {
+            return Err(GameApplyMoveError::InvalidMove);
+        }
+        position.play_move(mv)?;
+
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Some(Node {
+            parent: Some(parent),
+            mv: Some(mv),
+            position,
+            children: vec![],
+            starting_comment: None,
+            comment: None,
+            nags: vec![],
+        }));
+        self.node_mut(parent).children.push(id);
+
+        Ok(id)
+    }
+
+    /// Adds a move in SAN after a node, see `add_move`
+    ///
+    /// # Returns
+    /// * `Result<NodeId, ParseSanError>` - The node after the move, or an error if the SAN
+    ///   doesn't match a legal move
+    pub fn add_san(
+        &mut self,
+        parent: NodeId,
+        san: &str,
+    ) -> Result<NodeId, crate::error::ParseSanError> {
+        let mv = self.node(parent).position.parse_san(san)?;
+
+        Ok(self
+            .add_move(parent, mv)
+            .expect("parse_san only returns legal moves"))
+    }
+
+    /// Returns the parent of a node, or None for the root
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.node(node).parent
+    }
+
+    /// Returns the continuations from a node, the main line first
+    pub fn children(&self, node: NodeId) -> &[NodeId] {
+        &self.node(node).children
+    }
+
+    /// Returns the move that led to a node, or None for the root
+    pub fn mv(&self, node: NodeId) -> Option<Move> {
+        self.node(node).mv
+    }
+
+    /// Returns the position of a node, without any history
+    pub fn position(&self, node: NodeId) -> &Game {
+        &self.node(node).position
+    }
+
+    /// Returns the game at a node, with every move from the root in its history
+    ///
+    /// Moves played before the root aren't part of the history, see `new`.
+    pub fn game(&self, node: NodeId) -> Game {
+        let mut game = self.node(self.root()).position.clone();
+
+        for id in self.path(node) {
+            let mv = self.mv(id).expect("Only the root has no move");
+            game.apply_move(mv)
+                .expect("The moves in the tree are legal");
+        }
+
+        game
+    }
+
+    /// Returns the nodes from the root to a node, not including the root
+    pub fn path(&self, node: NodeId) -> Vec<NodeId> {
+        let mut path = vec![];
+        let mut current = node;
+
+        while let Some(parent) = self.parent(current) {
+            path.push(current);
+            current = parent;
+        }
+
+        path.reverse();
+        path
+    }
+
+    /// Returns the nodes of the main line, starting after the root
+    pub fn main_line(&self) -> Vec<NodeId> {
+        let mut line = vec![];
+        let mut current = self.root();
+
+        while let Some(next) = self.children(current).first() {
+            line.push(*next);
+            current = *next;
+        }
+
+        line
+    }
+
+    /// Returns the comment after the move of a node, for the root this is before the first move
+    pub fn comment(&self, node: NodeId) -> Option<&str> {
+        self.node(node).comment.as_deref()
+    }
+
+    /// Sets the comment after the move of a node, an empty comment removes it
+    pub fn set_comment(&mut self, node: NodeId, comment: &str) {
+        self.node_mut(node).comment = Some(comment.to_string()).filter(|c| !c.is_empty());
+    }
+
+    /// Returns the comment before the move of a node that starts a variation
+    pub fn starting_comment(&self, node: NodeId) -> Option<&str> {
+        self.node(node).starting_comment.as_deref()
+    }
+
+    /// Sets the comment before the move of a node, an empty comment removes it
+    ///
+    /// The comment is only written to PGN if the node starts a variation.
+    pub fn set_starting_comment(&mut self, node: NodeId, comment: &str) {
+        self.node_mut(node).starting_comment = Some(comment.to_string()).filter(|c| !c.is_empty());
+    }
+
+    /// Returns the numeric annotation glyphs of a node, e.g `1` for `!` and `2` for `?`
+    pub fn nags(&self, node: NodeId) -> &[u8] {
+        &self.node(node).nags
+    }
+
+    /// Adds a numeric annotation glyph to a node, if the node doesn't already have it
+    pub fn add_nag(&mut self, node: NodeId, nag: u8) {
+        let nags = &mut self.node_mut(node).nags;
+
+        if !nags.contains(&nag) {
+            nags.push(nag);
+        }
+    }
+
+    /// Removes a numeric annotation glyph from a node
+    pub fn remove_nag(&mut self, node: NodeId, nag: u8) {
+        self.node_mut(node).nags.retain(|n| *n != nag);
+    }
+
+    /// Moves a variation one step closer to the main line
+    ///
+    /// # Returns
+    /// * `bool` - If the variation was moved, false if it already is the main line
+    pub fn promote_variation(&mut self, node: NodeId) -> bool {
+        self.move_variation(node, -1)
+    }
+
+    /// Moves a variation one step further from the main line
+    ///
+    /// # Returns
+    /// * `bool` - If the variation was moved, false if it already is the last variation
+    pub fn demote_variation(&mut self, node: NodeId) -> bool {
+        self.move_variation(node, 1)
+    }
+
+    /// Makes a variation the main line after its parent
+    pub fn promote_to_main_line(&mut self, node: NodeId) {
+        while self.promote_variation(node) {}
+    }
+
+    fn move_variation(&mut self, node: NodeId, step: isize) -> bool {
+        let Some(parent) = self.parent(node) else {
+            return false;
+        };
+
+        let siblings = &mut self.node_mut(parent).children;
+        let index = siblings
+            .iter()
+            .position(|c| *c == node)
+            .expect("A node is a child of its parent");
+        let new_index = index as isize + step;
+
+        if new_index < 0 || new_index as usize >= siblings.len() {
+            return false;
+        }
+
+        siblings.swap(index, new_index as usize);
+        true
+    }
+
+    /// Removes a node and everything after it from the tree
+    ///
+    /// The root can't be deleted, deleting it does nothing.
+    pub fn delete_variation(&mut self, node: NodeId) {
+        let Some(parent) = self.parent(node) else {
+            return;
+        };
+
+        self.node_mut(parent).children.retain(|c| *c != node);
+
+        let mut to_delete = vec![node];
+        while let Some(id) = to_delete.pop() {
+            if let Some(removed) = self.nodes[id.0].take() {
+                to_delete.extend(removed.children);
+            }
+        }
+    }
+
+    /// Returns all tags, e.g `Event` or `White`
+    pub fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
+
+    /// Sets the value of a tag
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        self.tags.insert(name.to_string(), value.to_string());
+    }
+
+    /// Returns the result of the game
+    pub fn result(&self) -> PgnResult {
+        self.result
+    }
+
+    /// Sets the result of the game
+    pub fn set_result(&mut self, result: PgnResult) {
+        self.result = result;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_build_and_edit_variations() {
+        let mut tree = GameTree::new(Game::start_pos());
+        let root = tree.root();
+
+        let e4 = tree.add_san(root, "e4").unwrap();
+        let e5 = tree.add_san(e4, "e5").unwrap();
+        let c5 = tree.add_san(e4, "c5").unwrap();
+        let nf3 = tree.add_san(c5, "Nf3").unwrap();
+        let d4 = tree.add_san(root, "d4").unwrap();
+
+        assert_eq!(tree.add_san(root, "e4").unwrap(), e4);
+        assert_eq!(tree.main_line(), vec![e4, e5]);
+        assert_eq!(tree.children(e4), &[e5, c5]);
+        assert_eq!(tree.path(nf3), vec![e4, c5, nf3]);
+        assert_eq!(tree.game(nf3).ply(), 3);
+        assert_eq!(
+            tree.position(nf3).fen(),
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+
+        assert!(tree.promote_variation(c5));
+        assert!(!tree.promote_variation(c5));
+        assert_eq!(tree.main_line(), vec![e4, c5, nf3]);
+        assert!(tree.demote_variation(c5));
+        assert_eq!(tree.main_line(), vec![e4, e5]);
+
+        tree.promote_to_main_line(d4);
+        assert_eq!(tree.main_line(), vec![d4]);
+
+        tree.delete_variation(e4);
+        assert_eq!(tree.children(root), &[d4]);
+    }
+
+    #[test]
+    fn should_start_the_history_at_the_root() {
+        let mut game = Game::start_pos();
+        game.apply_move(game.parse_san("e4").unwrap()).unwrap();
+
+        let mut tree = GameTree::new(game);
+        let e5 = tree.add_san(tree.root(), "e5").unwrap();
+
+        assert!(tree.game(tree.root()).history().is_empty());
+        assert_eq!(tree.game(e5).history().len(), 1);
+        assert_eq!(tree.game(e5).get_fullmove_number(), 2);
+    }
+
+    #[test]
+    fn should_reject_illegal_moves() {
+        let mut tree = GameTree::new(Game::start_pos());
+        let root = tree.root();
+
+        assert!(tree
+            .add_move(
+                root,
+                Move::Quiet {
+                    from: (4, 6),
+                    to: (4, 3)
+                }
+            )
+            .is_err());
+        assert!(tree.children(root).is_empty());
+    }
+
+    #[test]
+    fn should_store_comments_and_nags() {
+        let mut tree = GameTree::new(Game::start_pos());
+        let e4 = tree.add_san(tree.root(), "e4").unwrap();
+
+        tree.set_comment(e4, "Best by test");
+        tree.add_nag(e4, 1);
+        tree.add_nag(e4, 1);
+        tree.add_nag(e4, 18);
+
+        assert_eq!(tree.comment(e4), Some("Best by test"));
+        assert_eq!(tree.nags(e4), &[1, 18]);
+
+        tree.remove_nag(e4, 1);
+        tree.set_comment(e4, "");
+
+        assert_eq!(tree.nags(e4), &[18]);
+        assert_eq!(tree.comment(e4), None);
+    }
+}
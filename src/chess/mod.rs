@@ -7,7 +7,24 @@ pub use game::*;
 mod board;
 pub use board::*;
 
+mod bitboard;
+pub use bitboard::*;
+
+pub mod attacks;
+
 pub mod error;
 
 mod mv;
 pub use mv::*;
+
+mod move_list;
+pub use move_list::*;
+
+mod pgn;
+pub use pgn::*;
+
+mod game_tree;
+pub use game_tree::*;
+
+mod square;
+pub use square::*;
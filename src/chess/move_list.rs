@@ -0,0 +1,112 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::Move;
+
+/// The most moves a `MoveList` can hold, no legal position has more than 218 moves
+pub const MAX_MOVES: usize = 256;
+
+/// A list of moves stored on the stack
+///
+/// Used with `Game::generate_legal_into` to generate moves without allocating, the list can be
+/// cleared and reused between positions. The list dereferences to a slice of the moves, so it can
+/// be iterated, indexed and sorted like one.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{Game, MoveList};
+///
+/// let game = Game::start_pos();
+/// let mut moves = MoveList::new();
+///
+/// game.generate_legal_into(&mut moves);
+/// assert_eq!(moves.len(), 20);
+/// assert!(moves.iter().all(|mv| !mv.is_capture()));
+/// ```
+#[derive(Clone, Copy)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    /// Creates an empty move list
+    pub const fn new() -> MoveList {
+        MoveList {
+            // The moves after `len` are never read, so any move works as a placeholder
+            moves: [Move::Quiet {
+                from: (0, 0),
+                to: (0, 0),
+            }; MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    /// Adds a move to the end of the list
+    ///
+    /// # Panics
+    /// If the list already holds `MAX_MOVES` moves
+    pub fn push(&mut self, mv: Move) {
+        assert!(
+            self.len < MAX_MOVES,
+            "A MoveList can't hold more than {} moves",
+            MAX_MOVES
+        );
+
+        self.moves[self.len] = mv;
+        self.len += 1;
+    }
+
+    /// Removes every move from the list
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Keeps only the moves that `f` returns true for, the order of the moves is kept
+    pub fn retain(&mut self, mut f: impl FnMut(&Move) -> bool) {
+        let mut kept = 0;
+
+        for i in 0..self.len {
+            if f(&self.moves[i]) {
+                self.moves[kept] = self.moves[i];
+                kept += 1;
+            }
+        }
+
+        self.len = kept;
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        MoveList::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl std::fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
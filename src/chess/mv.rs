@@ -1,4 +1,4 @@
-use crate::PieceType;
+use crate::{Color, Piece, PieceType, Square};
 
 /// A move that can be applied to a game
 ///
@@ -7,7 +7,7 @@ use crate::PieceType;
 ///
 /// A good way to render a move is to check `from()` and `to()` first, if you need to render the
 /// capture square you can use `capture()`.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Move {
     /// A move that is not a capture
     Quiet {
@@ -48,27 +48,88 @@ pub enum Move {
 }
 
 impl Move {
-    pub fn is_double_pawn_push(&self) -> bool {
-        match self {
-            Move::DoublePawnPush { .. } => true,
-            _ => false,
+    /// Creates a move that is not a capture
+    pub fn new_quiet(from: Square, to: Square) -> Move {
+        Move::Quiet {
+            from: from.coords(),
+            to: to.coords(),
         }
     }
 
-    pub fn is_capture(&self) -> bool {
-        match self {
-            Move::Capture { .. } | Move::CapturePromotion { .. } => true,
-            _ => false,
+    /// Creates a double pawn push
+    pub fn new_double_pawn_push(from: Square, to: Square) -> Move {
+        Move::DoublePawnPush {
+            from: from.coords(),
+            to: to.coords(),
         }
     }
 
-    pub fn is_castle(&self) -> bool {
-        match self {
-            Move::Castle { .. } => true,
-            _ => false,
+    /// Creates a capture
+    ///
+    /// # Arguments
+    /// * `from` - The square of the capturing piece
+    /// * `to` - The square the capturing piece moves to
+    /// * `capture` - The square of the captured piece, only different from `to` for en passant
+    pub fn new_capture(from: Square, to: Square, capture: Square) -> Move {
+        Move::Capture {
+            from: from.coords(),
+            to: to.coords(),
+            capture: capture.coords(),
         }
     }
 
+    /// Creates a castle
+    ///
+    /// # Arguments
+    /// * `from` - The square of the king
+    /// * `to` - The square the king moves to
+    /// * `rook_from` - The square of the rook
+    /// * `rook_to` - The square the rook moves to
+    pub fn new_castle(from: Square, to: Square, rook_from: Square, rook_to: Square) -> Move {
+        Move::Castle {
+            from: from.coords(),
+            to: to.coords(),
+            rook_from: rook_from.coords(),
+            rook_to: rook_to.coords(),
+        }
+    }
+
+    /// Creates a promotion that is not a capture
+    pub fn new_quiet_promotion(from: Square, to: Square, promotion: PieceType) -> Move {
+        Move::QuietPromotion {
+            from: from.coords(),
+            to: to.coords(),
+            promotion,
+        }
+    }
+
+    /// Creates a capture that is a promotion
+    pub fn new_capture_promotion(
+        from: Square,
+        to: Square,
+        capture: Square,
+        promotion: PieceType,
+    ) -> Move {
+        Move::CapturePromotion {
+            from: from.coords(),
+            to: to.coords(),
+            capture: capture.coords(),
+            promotion,
+        }
+    }
+
+    pub fn is_double_pawn_push(&self) -> bool {
+        matches!(self, Move::DoublePawnPush { .. })
+    }
+
+    pub fn is_capture(&self) -> bool {
+        matches!(self, Move::Capture { .. } | Move::CapturePromotion { .. })
+    }
+
+    pub fn is_castle(&self) -> bool {
+        matches!(self, Move::Castle { .. })
+    }
+
     pub fn is_queen_side_castle(&self) -> bool {
         match self {
             Move::Castle { rook_from, .. } => rook_from.0 == 0,
@@ -84,10 +145,10 @@ impl Move {
     }
 
     pub fn is_promotion(&self) -> bool {
-        match self {
-            Move::QuietPromotion { .. } | Move::CapturePromotion { .. } => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            Move::QuietPromotion { .. } | Move::CapturePromotion { .. }
+        )
     }
 
     /// Returns the move from square. If the move is a castle, it returns the king square
@@ -114,6 +175,22 @@ impl Move {
         }
     }
 
+    /// Returns the move from square as a `Square`, see `from`
+    ///
+    /// # Panics
+    /// If the from coordinates are outside the board
+    pub fn from_square(&self) -> Square {
+        Square::try_from(self.from()).expect("Moves are always on the board")
+    }
+
+    /// Returns the move to square as a `Square`, see `to`
+    ///
+    /// # Panics
+    /// If the to coordinates are outside the board
+    pub fn to_square(&self) -> Square {
+        Square::try_from(self.to()).expect("Moves are always on the board")
+    }
+
     pub fn capture(&self) -> Option<(usize, usize)> {
         match self {
             Move::Capture { capture, .. } | Move::CapturePromotion { capture, .. } => {
@@ -147,4 +224,48 @@ impl Move {
             _ => None,
         }
     }
+
+    /// Returns the move in UCI long algebraic notation, e.g `e2e4` or `e7e8q`
+    ///
+    /// Castling is written as the king move, e.g `e1g1`.
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Move, Square};
+    ///
+    /// let mv = Move::new_double_pawn_push(Square::E2, Square::E4);
+    ///
+    /// assert_eq!(mv.to_uci(), "e2e4");
+    /// ```
+    ///
+    /// # Panics
+    /// If the from or to coordinates are outside the board, see `try_to_uci` for a version that
+    /// doesn't panic
+    pub fn to_uci(&self) -> String {
+        self.try_to_uci()
+            .expect("from and to must be between 0 and 7")
+    }
+
+    /// Returns the move in UCI long algebraic notation, see `to_uci`
+    ///
+    /// # Returns
+    /// * `Option<String>` - The move in UCI notation, or None if the from or to coordinates are
+    ///   outside the board
+    pub fn try_to_uci(&self) -> Option<String> {
+        let from = Square::try_from(self.from()).ok()?;
+        let to = Square::try_from(self.to()).ok()?;
+        let mut uci = format!("{}{}", from, to);
+
+        if let Some(promotion) = self.promotion() {
+            uci.push(
+                Piece {
+                    piece_type: promotion,
+                    color: Color::Black,
+                }
+                .into(),
+            );
+        }
+
+        Some(uci)
+    }
 }
@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+
+use crate::{Color, Game, Outcome, PgnGame, PgnResult};
+
+/// The tags that every exported game has, in the order they're exported
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+/// The maximum length of a line of movetext
+pub(super) const LINE_LENGTH: usize = 80;
+
+impl PgnGame {
+    /// Returns the game in PGN export format
+    ///
+    /// The seven tag roster is written first in its standard order, followed by `SetUp` and
+    /// `FEN` if the game doesn't start from the starting position and then all other tags in
+    /// alphabetical order. The movetext is wrapped at 80 columns and ends with the result, which
+    /// is derived from the outcome of the game if the game is over.
+    ///
+    /// # Returns
+    /// * `String` - The game in PGN
+    pub fn to_pgn(&self) -> String {
+        let result = export_result(&self.game, self.result);
+        let start = self.game.starting_position();
+
+        let mut pgn = tag_section(&self.tags, &start, result);
+        pgn.push_str(&wrap(&self.movetext_tokens(start, result), LINE_LENGTH));
+        pgn.push('\n');
+
+        pgn
+    }
+
+    /// Internal helper that returns the movetext as tokens separated by spaces
+    fn movetext_tokens(&self, start: Game, result: PgnResult) -> Vec<String> {
+        let mut tokens = vec![];
+        let mut game = start;
+        // A black move needs a move number if it's the first move or comes after a comment
+        let mut needs_number = true;
+
+        for (ply, entry) in self.game.history().iter().enumerate() {
+            if let Some(comment) = self.comment(ply) {
+                push_comment(&mut tokens, comment);
+                needs_number = true;
+            }
+
+            let number = game.get_fullmove_number();
+            if game.get_turn() == Color::White {
+                tokens.push(format!("{}.", number));
+            } else if needs_number {
+                tokens.push(format!("{}...", number));
+            }
+
+            tokens.push(game.san(entry.mv));
+            needs_number = false;
+
+            game.play_move(entry.mv)
+                .expect("The move was legal when it was applied");
+        }
+
+        if let Some(comment) = self.comment(self.game.ply()) {
+            push_comment(&mut tokens, comment);
+        }
+
+        tokens.push(result.to_string());
+
+        tokens
+    }
+}
+
+impl Game {
+    /// Returns the game in PGN export format, with all tags except `Result` set to unknown
+    ///
+    /// See `PgnGame::to_pgn` for how the game is written.
+    ///
+    /// # Returns
+    /// * `String` - The game in PGN
+    pub fn to_pgn(&self) -> String {
+        PgnGame::new(self.clone()).to_pgn()
+    }
+}
+
+/// Internal helper that returns the result of a finished game, or `result` if it isn't over
+pub(super) fn export_result(game: &Game, result: PgnResult) -> PgnResult {
+    match game.outcome() {
+        Some(Outcome::Decisive {
+            winner: Color::White,
+            ..
+        }) => PgnResult::WhiteWins,
+        Some(Outcome::Decisive {
+            winner: Color::Black,
+            ..
+        }) => PgnResult::BlackWins,
+        Some(Outcome::Draw { .. }) => PgnResult::Draw,
+        None => result,
+    }
+}
+
+/// Internal helper that writes the tag pair section followed by the empty line before the movetext
+///
+/// The seven tag roster comes first, then `SetUp` and `FEN` if `start` isn't the starting
+/// position and then all other tags.
+pub(super) fn tag_section(
+    tags: &BTreeMap<String, String>,
+    start: &Game,
+    result: PgnResult,
+) -> String {
+    let mut pgn = String::new();
+
+    for (name, default) in SEVEN_TAG_ROSTER {
+        let value = if name == "Result" {
+            result.to_string()
+        } else {
+            tags.get(name)
+                .map(|v| v.as_str())
+                .unwrap_or(default)
+                .to_string()
+        };
+
+        push_tag(&mut pgn, name, &value);
+    }
+
+    if *start != Game::start_pos() {
+        push_tag(&mut pgn, "SetUp", "1");
+        push_tag(&mut pgn, "FEN", &start.fen());
+    }
+
+    for (name, value) in tags {
+        let derived = ["SetUp", "FEN"].contains(&name.as_str());
+        let in_roster = SEVEN_TAG_ROSTER.iter().any(|(n, _)| n == name);
+
+        if !derived && !in_roster {
+            push_tag(&mut pgn, name, value);
+        }
+    }
+
+    pgn.push('\n');
+
+    pgn
+}
+
+/// Internal helper that writes a tag pair on its own line
+fn push_tag(pgn: &mut String, name: &str, value: &str) {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+
+    pgn.push_str(&format!("[{} \"{}\"]\n", name, value));
+}
+
+/// Internal helper that adds a comment as one token per word, so that it can be wrapped
+pub(super) fn push_comment(tokens: &mut Vec<String>, comment: &str) {
+    let comment = format!("{{{}}}", comment.replace('}', ""));
+
+    tokens.extend(comment.split_whitespace().map(|w| w.to_string()));
+}
+
+/// Internal helper that joins tokens with spaces into lines that are at most `width` long
+pub(super) fn wrap(tokens: &[String], width: usize) -> String {
+    let mut text = String::new();
+    let mut line_length = 0;
+
+    for token in tokens {
+        if line_length > 0 && line_length + 1 + token.len() > width {
+            text.push('\n');
+            line_length = 0;
+        } else if line_length > 0 {
+            text.push(' ');
+            line_length += 1;
+        }
+
+        text.push_str(token);
+        line_length += token.len();
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_export_a_played_game() {
+        let mut game = Game::start_pos();
+
+        for san in ["f3", "e5", "g4", "Qh4"] {
+            let mv = game.parse_san(san).unwrap();
+            game.apply_move(mv).unwrap();
+        }
+
+        assert_eq!(
+            game.to_pgn(),
+            "[Event \"?\"]\n\
+             [Site \"?\"]\n\
+             [Date \"????.??.??\"]\n\
+             [Round \"?\"]\n\
+             [White \"?\"]\n\
+             [Black \"?\"]\n\
+             [Result \"0-1\"]\n\
+             \n\
+             1. f3 e5 2. g4 Qh4# 0-1\n"
+        );
+    }
+
+    #[test]
+    fn should_export_setup_comments_and_extra_tags() {
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 0 30").unwrap();
+
+        for san in ["Kf7", "O-O-O", "Ke7"] {
+            let mv = game.parse_san(san).unwrap();
+            game.apply_move(mv).unwrap();
+        }
+
+        let mut pgn_game = PgnGame::new(game);
+        pgn_game.set_tag("White", "Fritiof \"the rook\"");
+        pgn_game.set_tag("Annotator", "Fritiof");
+        pgn_game.set_comment(0, "Black to move");
+        pgn_game.set_comment(2, "Castling long");
+        pgn_game.set_result(PgnResult::WhiteWins);
+
+        assert_eq!(
+            pgn_game.to_pgn(),
+            "[Event \"?\"]\n\
+             [Site \"?\"]\n\
+             [Date \"????.??.??\"]\n\
+             [Round \"?\"]\n\
+             [White \"Fritiof \\\"the rook\\\"\"]\n\
+             [Black \"?\"]\n\
+             [Result \"1-0\"]\n\
+             [SetUp \"1\"]\n\
+             [FEN \"4k3/8/8/8/8/8/8/R3K3 b Q - 0 30\"]\n\
+             [Annotator \"Fritiof\"]\n\
+             \n\
+             {Black to move} 30... Kf7 31. O-O-O {Castling long} 31... Ke7 1-0\n"
+        );
+    }
+
+    #[test]
+    fn export_should_round_trip_and_wrap() {
+        let pgn = "[Event \"Wrapped\"]\n\n1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 {This opening is called \
+                   the Ruy Lopez, and this comment is long enough to be wrapped} 4. Ba4 Nf6 \
+                   5. O-O Be7 6. Re1 b5 7. Bb3 d6 8. c3 O-O 9. h3 Nb8 10. d4 Nbd7 11. c4 c6 \
+                   12. cxb5 axb5 13. Nc3 Bb7 14. Bg5 b4 15. Nb1 h6 16. Bh4 c5 17. dxe5 Nxe4 *";
+
+        let pgn_game = pgn.parse::<PgnGame>().unwrap();
+        let exported = pgn_game.to_pgn();
+
+        assert!(exported.lines().all(|line| line.len() <= LINE_LENGTH));
+
+        let reparsed = exported.parse::<PgnGame>().unwrap();
+        assert_eq!(reparsed.game().fen(), pgn_game.game().fen());
+        assert_eq!(reparsed.tag("Event"), Some("Wrapped"));
+        assert_eq!(reparsed.comment(6), pgn_game.comment(6));
+        assert_eq!(reparsed.to_pgn(), exported);
+    }
+}
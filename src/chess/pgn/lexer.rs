@@ -0,0 +1,321 @@
+use crate::{error::ParsePgnError, PgnResult};
+
+/// A token in a PGN file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    /// `[`
+    TagStart,
+    /// `]`
+    TagEnd,
+    /// A quoted string with the escapes removed
+    String(String),
+    /// A move number like `12.` or `12...`
+    MoveNumber(u32),
+    /// A symbol, this is either a move or a tag name
+    Symbol(&'a str),
+    /// A `{ ... }` or `; ...` comment without the delimiters
+    Comment(&'a str),
+    /// A numeric annotation glyph like `$1`, `!` is turned into `$1`
+    Nag(u8),
+    /// `(`
+    VariationStart,
+    /// `)`
+    VariationEnd,
+    /// A game termination marker like `1-0`
+    Result(PgnResult),
+}
+
+impl std::fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::TagStart => write!(f, "["),
+            Token::TagEnd => write!(f, "]"),
+            Token::String(s) => write!(f, "\"{}\"", s),
+            Token::MoveNumber(n) => write!(f, "{}.", n),
+            Token::Symbol(s) => write!(f, "{}", s),
+            Token::Comment(c) => write!(f, "{{{}}}", c),
+            Token::Nag(n) => write!(f, "${}", n),
+            Token::VariationStart => write!(f, "("),
+            Token::VariationEnd => write!(f, ")"),
+            Token::Result(r) => write!(f, "{}", r),
+        }
+    }
+}
+
+/// Splits a PGN string into tokens
+pub(crate) struct Lexer<'a> {
+    input: &'a str,
+    pos: usize,
+    /// A token that was split off the previous one, e.g the `!` in `e4!`
+    pending: Option<Token<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    pub(crate) fn new(input: &'a str) -> Lexer<'a> {
+        Lexer {
+            input,
+            pos: 0,
+            pending: None,
+        }
+    }
+
+    /// Returns the next token without consuming it
+    pub(crate) fn peek(&self) -> Option<Result<Token<'a>, ParsePgnError>> {
+        Lexer {
+            input: self.input,
+            pos: self.pos,
+            pending: self.pending.clone(),
+        }
+        .next()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    /// Skips whitespace and `%` escape lines
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+
+            let at_line_start = self.input[..self.pos].ends_with('\n') || self.pos == 0;
+            if at_line_start && trimmed.starts_with('%') {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Reads a quoted string, the opening quote is already consumed
+    fn string(&mut self) -> Result<Token<'a>, ParsePgnError> {
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += i + 1;
+                    return Ok(Token::String(value));
+                }
+                '\\' => {
+                    if let Some((_, escaped)) = chars.next() {
+                        value.push(escaped);
+                    }
+                }
+                '\n' => {
+                    self.pos += i;
+                    return Err(ParsePgnError::UnterminatedString);
+                }
+                c => value.push(c),
+            }
+        }
+
+        self.pos = self.input.len();
+        Err(ParsePgnError::UnterminatedString)
+    }
+
+    /// Reads a symbol and splits off any move number prefix or annotation suffix
+    fn symbol(&mut self) -> Result<Token<'a>, ParsePgnError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !is_symbol_char(c))
+            .unwrap_or(rest.len());
+        let symbol = &rest[..len];
+
+        if len == 0 {
+            let c = rest.chars().next().expect("The input is not empty");
+            self.pos += c.len_utf8();
+            return Err(ParsePgnError::UnexpectedCharacter(c));
+        }
+
+        if let Ok(result) = symbol.parse::<PgnResult>() {
+            self.pos += len;
+            return Ok(Token::Result(result));
+        }
+
+        // Move numbers like `12.` or `12...`, possibly directly followed by the move
+        let digits = symbol
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(symbol.len());
+        if digits > 0 && symbol[digits..].starts_with('.') {
+            let dots = symbol[digits..]
+                .find(|c: char| c != '.')
+                .unwrap_or(symbol.len() - digits);
+            self.pos += digits + dots;
+
+            return symbol[..digits]
+                .parse::<u32>()
+                .map(Token::MoveNumber)
+                .map_err(|_| ParsePgnError::UnexpectedCharacter('.'));
+        }
+
+        self.pos += len;
+
+        let annotation_start = symbol.find(['!', '?']).filter(|i| *i > 0).unwrap_or(len);
+        let (symbol, annotation) = symbol.split_at(annotation_start);
+
+        if !annotation.is_empty() {
+            self.pending = Some(
+                annotation_nag(annotation)
+                    .map(Token::Nag)
+                    .ok_or(ParsePgnError::UnexpectedCharacter('?'))?,
+            );
+        }
+
+        Ok(Token::Symbol(symbol))
+    }
+
+    /// Reads an annotation like `!?` that is separated from its move
+    fn standalone_annotation(&mut self) -> Result<Token<'a>, ParsePgnError> {
+        let rest = self.rest();
+        let len = rest.find(|c| c != '!' && c != '?').unwrap_or(rest.len());
+        self.pos += len;
+
+        annotation_nag(&rest[..len])
+            .map(Token::Nag)
+            .ok_or(ParsePgnError::UnexpectedCharacter('?'))
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Result<Token<'a>, ParsePgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(token) = self.pending.take() {
+            return Some(Ok(token));
+        }
+
+        self.skip_whitespace();
+
+        let c = self.rest().chars().next()?;
+
+        let token = match c {
+            '[' => Ok(Token::TagStart),
+            ']' => Ok(Token::TagEnd),
+            '(' => Ok(Token::VariationStart),
+            ')' => Ok(Token::VariationEnd),
+            '"' => {
+                self.pos += 1;
+                return Some(self.string());
+            }
+            '{' => {
+                let rest = &self.rest()[1..];
+                return Some(match rest.find('}') {
+                    Some(end) => {
+                        self.pos += end + 2;
+                        Ok(Token::Comment(&rest[..end]))
+                    }
+                    None => {
+                        self.pos = self.input.len();
+                        Err(ParsePgnError::UnterminatedComment)
+                    }
+                });
+            }
+            ';' => {
+                let rest = &self.rest()[1..];
+                let end = rest.find('\n').unwrap_or(rest.len());
+                self.pos += end + 1;
+                return Some(Ok(Token::Comment(rest[..end].trim_end_matches('\r'))));
+            }
+            '$' => {
+                let rest = &self.rest()[1..];
+                let len = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                self.pos += len + 1;
+                return Some(
+                    rest[..len]
+                        .parse::<u8>()
+                        .map(Token::Nag)
+                        .map_err(|_| ParsePgnError::UnexpectedCharacter('$')),
+                );
+            }
+            '*' => Ok(Token::Result(PgnResult::Ongoing)),
+            '!' | '?' => return Some(self.standalone_annotation()),
+            _ => return Some(self.symbol()),
+        };
+
+        self.pos += 1;
+
+        Some(token)
+    }
+}
+
+/// Returns if a character can be part of a symbol token
+fn is_symbol_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_+#=:-/.!?".contains(c)
+}
+
+/// Returns the NAG that a move suffix annotation like `!?` stands for
+fn annotation_nag(annotation: &str) -> Option<u8> {
+    match annotation {
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_tokenize_pgn() {
+        let pgn = "[Event \"Test \\\"game\\\"\"]\n\
+                   %escaped line\n\
+                   1.e4 e5 2. Nf3!? {A comment} (2. f4 $2) 2... Nc6 ; rest of line\n\
+                   3.Bb5 ?? 1/2-1/2";
+
+        let tokens = Lexer::new(pgn)
+            .collect::<Result<Vec<Token>, ParsePgnError>>()
+            .unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::TagStart,
+                Token::Symbol("Event"),
+                Token::String("Test \"game\"".to_string()),
+                Token::TagEnd,
+                Token::MoveNumber(1),
+                Token::Symbol("e4"),
+                Token::Symbol("e5"),
+                Token::MoveNumber(2),
+                Token::Symbol("Nf3"),
+                Token::Nag(5),
+                Token::Comment("A comment"),
+                Token::VariationStart,
+                Token::MoveNumber(2),
+                Token::Symbol("f4"),
+                Token::Nag(2),
+                Token::VariationEnd,
+                Token::MoveNumber(2),
+                Token::Symbol("Nc6"),
+                Token::Comment(" rest of line"),
+                Token::MoveNumber(3),
+                Token::Symbol("Bb5"),
+                Token::Nag(4),
+                Token::Result(PgnResult::Draw),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_unterminated_comments_and_strings() {
+        assert!(matches!(
+            Lexer::new("1. e4 {never closed").nth(2),
+            Some(Err(ParsePgnError::UnterminatedComment))
+        ));
+        assert!(matches!(
+            Lexer::new("[Event \"never closed]").nth(2),
+            Some(Err(ParsePgnError::UnterminatedString))
+        ));
+    }
+}
@@ -0,0 +1,376 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use crate::{error::ParsePgnError, Game};
+
+mod export;
+mod lexer;
+use lexer::{Lexer, Token};
+mod reader;
+pub use reader::*;
+mod tree;
+
+/// The result of a game as written in PGN
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PgnResult {
+    /// `1-0`
+    WhiteWins,
+    /// `0-1`
+    BlackWins,
+    /// `1/2-1/2`
+    Draw,
+    /// `*`, the game is still going on or the result is unknown
+    Ongoing,
+}
+
+impl FromStr for PgnResult {
+    type Err = ParsePgnError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1-0" => Ok(PgnResult::WhiteWins),
+            "0-1" => Ok(PgnResult::BlackWins),
+            "1/2-1/2" => Ok(PgnResult::Draw),
+            "*" => Ok(PgnResult::Ongoing),
+            _ => Err(ParsePgnError::UnknownResult(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for PgnResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let result = match self {
+            PgnResult::WhiteWins => "1-0",
+            PgnResult::BlackWins => "0-1",
+            PgnResult::Draw => "1/2-1/2",
+            PgnResult::Ongoing => "*",
+        };
+
+        write!(f, "{}", result)
+    }
+}
+
+/// A game in PGN
+///
+/// Holds the tags of the game, the game itself with all the moves in its history, the comments
+/// on the moves and the result written at the end of the movetext.
+#[derive(Debug, Clone)]
+pub struct PgnGame {
+    tags: BTreeMap<String, String>,
+    game: Game,
+    /// Comments keyed by the ply they come after, 0 is before the first move
+    comments: BTreeMap<usize, String>,
+    result: PgnResult,
+}
+
+impl PgnGame {
+    /// Creates a PGN game without any tags or comments from a game
+    ///
+    /// # Arguments
+    /// * `game` - The game, every move in its history becomes a move in the movetext
+    pub fn new(game: Game) -> PgnGame {
+        PgnGame {
+            tags: BTreeMap::new(),
+            game,
+            comments: BTreeMap::new(),
+            result: PgnResult::Ongoing,
+        }
+    }
+
+    /// Returns all tags of the game, e.g `Event` or `White`
+    pub fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
+
+    /// Returns the value of a tag
+    ///
+    /// # Arguments
+    /// * `name` - The name of the tag, e.g `White`
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The value of the tag, or None if the game doesn't have the tag
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.get(name).map(|v| v.as_str())
+    }
+
+    /// Sets the value of a tag
+    ///
+    /// The `Result`, `SetUp` and `FEN` tags are always derived from the game when exporting.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the tag, e.g `White`
+    /// * `value` - The value of the tag
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        self.tags.insert(name.to_string(), value.to_string());
+    }
+
+    /// Returns the comment after a move
+    ///
+    /// # Arguments
+    /// * `ply` - The amount of moves played before the comment, 0 is before the first move
+    pub fn comment(&self, ply: usize) -> Option<&str> {
+        self.comments.get(&ply).map(|c| c.as_str())
+    }
+
+    /// Sets the comment after a move, an empty comment removes it
+    ///
+    /// # Arguments
+    /// * `ply` - The amount of moves played before the comment, 0 is before the first move
+    /// * `comment` - The comment text
+    pub fn set_comment(&mut self, ply: usize, comment: &str) {
+        if comment.is_empty() {
+            self.comments.remove(&ply);
+        } else {
+            self.comments.insert(ply, comment.to_string());
+        }
+    }
+
+    /// Returns the game with all the moves applied
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Takes the game out of the PGN game
+    pub fn into_game(self) -> Game {
+        self.game
+    }
+
+    /// Returns the result written at the end of the movetext
+    pub fn result(&self) -> PgnResult {
+        self.result
+    }
+
+    /// Sets the result, this is used when the game has ended in a way that can't be seen from the
+    /// position, e.g a resignation or a draw by agreement
+    pub fn set_result(&mut self, result: PgnResult) {
+        self.result = result;
+    }
+}
+
+impl FromStr for PgnGame {
+    type Err = ParsePgnError;
+
+    /// Parses a single game in PGN
+    ///
+    /// The game starts from the position in the `FEN` tag if there is one, otherwise from the
+    /// starting position. Every move is checked against the legal moves of the game. Comments on
+    /// the main line are kept, annotations and variations are skipped.
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{PgnGame, PgnResult};
+    ///
+    /// let pgn = "[White \"Fritiof\"]\n\n1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0";
+    /// let pgn_game = pgn.parse::<PgnGame>().unwrap();
+    ///
+    /// assert_eq!(pgn_game.tag("White"), Some("Fritiof"));
+    /// assert_eq!(pgn_game.game().ply(), 7);
+    /// assert_eq!(pgn_game.result(), PgnResult::WhiteWins);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lexer = Lexer::new(s);
+
+        let tags = parse_tags(&mut lexer)?;
+
+        parse_game(tags, &mut lexer)
+    }
+}
+
+/// Internal helper that reads the movetext of a game whose tags have already been read
+fn parse_game(tags: BTreeMap<String, String>, lexer: &mut Lexer) -> Result<PgnGame, ParsePgnError> {
+    let mut game = starting_position(&tags)?;
+    let mut comments = BTreeMap::new();
+    let result = parse_movetext(lexer, &mut game, &mut comments)?;
+
+    if let Some(token) = lexer.next() {
+        return Err(ParsePgnError::UnexpectedToken {
+            move_number: game.get_fullmove_number(),
+            token: token?.to_string(),
+        });
+    }
+
+    Ok(PgnGame {
+        tags,
+        game,
+        comments,
+        result,
+    })
+}
+
+/// Internal helper that reads the tag pair section of a game
+fn parse_tags(lexer: &mut Lexer) -> Result<BTreeMap<String, String>, ParsePgnError> {
+    let mut tags = BTreeMap::new();
+
+    while let Some(Ok(Token::TagStart)) = lexer.peek() {
+        lexer.next();
+
+        let name = match lexer.next().transpose()? {
+            Some(Token::Symbol(name)) => name.to_string(),
+            token => return Err(invalid_tag(token)),
+        };
+        let value = match lexer.next().transpose()? {
+            Some(Token::String(value)) => value,
+            token => return Err(invalid_tag(token)),
+        };
+        match lexer.next().transpose()? {
+            Some(Token::TagEnd) => (),
+            token => return Err(invalid_tag(token)),
+        }
+
+        tags.insert(name, value);
+    }
+
+    Ok(tags)
+}
+
+fn invalid_tag(token: Option<Token>) -> ParsePgnError {
+    ParsePgnError::InvalidTag(token.map(|t| t.to_string()).unwrap_or_default())
+}
+
+/// Internal helper that returns the position a game starts from, based on the `FEN` tag
+fn starting_position(tags: &BTreeMap<String, String>) -> Result<Game, ParsePgnError> {
+    match tags.get("FEN") {
+        Some(fen) if tags.get("SetUp").is_none_or(|s| s == "1") => {
+            Game::from_fen(fen).map_err(ParsePgnError::InvalidFen)
+        }
+        _ => Ok(Game::start_pos()),
+    }
+}
+
+/// Internal helper that reads the movetext of a game and applies every move to the game
+///
+/// Comments on the main line are added to `comments`, keyed by the ply they come after.
+///
+/// # Returns
+/// * `Result<PgnResult, ParsePgnError>` - The game termination marker, if the movetext ends
+///   without one the result is `PgnResult::Ongoing`
+fn parse_movetext(
+    lexer: &mut Lexer,
+    game: &mut Game,
+    comments: &mut BTreeMap<usize, String>,
+) -> Result<PgnResult, ParsePgnError> {
+    let mut variation_depth = 0;
+
+    for token in lexer.by_ref() {
+        let token = token?;
+
+        match token {
+            Token::VariationStart => variation_depth += 1,
+            Token::VariationEnd if variation_depth > 0 => variation_depth -= 1,
+            _ if variation_depth > 0 => (),
+            Token::MoveNumber(_) | Token::Nag(_) => (),
+            Token::Comment(comment) => {
+                append_comment(comments.entry(game.ply()).or_default(), comment);
+            }
+            Token::Symbol(san) => {
+                let mv = game
+                    .parse_san(san)
+                    .map_err(|source| ParsePgnError::InvalidMove {
+                        move_number: game.get_fullmove_number(),
+                        token: san.to_string(),
+                        source,
+                    })?;
+
+                game.apply_move(mv)
+                    .expect("parse_san only returns legal moves");
+            }
+            Token::Result(result) => return Ok(result),
+            token => {
+                return Err(ParsePgnError::UnexpectedToken {
+                    move_number: game.get_fullmove_number(),
+                    token: token.to_string(),
+                })
+            }
+        }
+    }
+
+    if variation_depth > 0 {
+        return Err(ParsePgnError::UnterminatedVariation);
+    }
+
+    Ok(PgnResult::Ongoing)
+}
+
+/// Internal helper that adds a comment to the comments already read at the same place
+fn append_comment(existing: &mut String, comment: &str) {
+    if !existing.is_empty() {
+        existing.push(' ');
+    }
+    // Line breaks in a comment are only there to wrap the text
+    existing.push_str(&comment.split_whitespace().collect::<Vec<_>>().join(" "));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseSanError;
+
+    #[test]
+    fn should_parse_pgn() {
+        let pgn = r#"[Event "F/S Return Match"]
+[Site "Belgrade, Serbia JUG"]
+[Date "1992.11.04"]
+[Round "29"]
+[White "Fischer, Robert J."]
+[Black "Spassky, Boris V."]
+[Result "1/2-1/2"]
+
+1. e4 e5 2. Nf3 Nc6 3. Bb5 a6 {This opening is called the Ruy Lopez.}
+4. Ba4 Nf6 5. O-O Be7 6. Re1 b5 7. Bb3 d6 8. c3 O-O 9. h3 Nb8 10. d4 Nbd7
+11. c4 c6 12. cxb5 axb5 13. Nc3 Bb7 14. Bg5 b4 15. Nb1 h6 16. Bh4 c5 17. dxe5
+Nxe4 18. Bxe7 Qxe7 19. exd6 Qf6 20. Nbd2 Nxd6 21. Nc4 Nxc4 22. Bxc4 Nb6
+23. Ne5 Rae8 24. Bxf7+ Rxf7 25. Nxf7 Rxe1+ 26. Qxe1 Kxf7 27. Qe3 Qg5 28. Qxg5
+hxg5 29. b3 Ke6 30. a3 Kd6 31. axb4 cxb4 32. Ra5 Nd5 33. f3 Bc8 34. Kf2 Bf5
+35. Ra7 g6 36. Ra6+ Kc5 37. Ke1 Nf4 38. g3 Nxh3 39. Kd2 Kb5 40. Rd6 Kc5 41. Ra6
+Nf2 42. g4 Bd3 43. Re6 1/2-1/2"#;
+
+        let pgn_game = pgn.parse::<PgnGame>().unwrap();
+
+        assert_eq!(pgn_game.tag("White"), Some("Fischer, Robert J."));
+        assert_eq!(pgn_game.tags().len(), 7);
+        assert_eq!(pgn_game.result(), PgnResult::Draw);
+        assert_eq!(pgn_game.game().ply(), 85);
+        assert_eq!(
+            pgn_game.game().fen(),
+            "8/8/4R1p1/2k3p1/1p4P1/1P1b1P2/3K1n2/8 b - - 2 43"
+        );
+    }
+
+    #[test]
+    fn should_start_from_the_fen_tag() {
+        let pgn = r#"[SetUp "1"]
+[FEN "4k3/8/8/8/8/8/8/R3K3 w Q - 0 30"]
+
+30. O-O-O Kf7 (30... Ke7 31. Rd7+) 31. Kb1 *"#;
+
+        let pgn_game = pgn.parse::<PgnGame>().unwrap();
+
+        assert_eq!(pgn_game.result(), PgnResult::Ongoing);
+        assert_eq!(pgn_game.game().fen(), "8/5k2/8/8/8/8/8/1K1R4 b - - 3 31");
+    }
+
+    #[test]
+    fn should_point_to_the_offending_move() {
+        let pgn = "1. e4 e5 2. Nf3 Nc6 3. Bb6 a6 *";
+
+        match pgn.parse::<PgnGame>() {
+            Err(ParsePgnError::InvalidMove {
+                move_number,
+                token,
+                source: ParseSanError::IllegalMove,
+            }) => {
+                assert_eq!(move_number, 3);
+                assert_eq!(token, "Bb6");
+            }
+            result => panic!("Expected an invalid move, got {:?}", result),
+        }
+
+        assert!(matches!(
+            "[Event Test]".parse::<PgnGame>(),
+            Err(ParsePgnError::InvalidTag(_))
+        ));
+        assert!(matches!(
+            "1. e4 (1. d4 *".parse::<PgnGame>(),
+            Err(ParsePgnError::UnterminatedVariation)
+        ));
+    }
+}
@@ -0,0 +1,344 @@
+use std::{collections::BTreeMap, io::BufRead};
+
+use super::{lexer::Lexer, parse_game, parse_tags};
+use crate::{
+    error::{ParsePgnError, ReadPgnError},
+    PgnGame,
+};
+
+/// A game from a PGN database whose moves haven't been replayed yet
+///
+/// Reading the tags is cheap, the movetext is only checked and applied to a `Game` when `replay`
+/// is called. This makes it possible to filter a large database on e.g the players before doing
+/// the expensive work.
+#[derive(Debug, Clone)]
+pub struct RawPgnGame {
+    tags: BTreeMap<String, String>,
+    movetext: String,
+    line: usize,
+}
+
+impl RawPgnGame {
+    /// Returns all tags of the game
+    pub fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
+
+    /// Returns the value of a tag
+    ///
+    /// # Arguments
+    /// * `name` - The name of the tag, e.g `White`
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.get(name).map(|v| v.as_str())
+    }
+
+    /// Returns the movetext of the game as it was written in the file
+    pub fn movetext(&self) -> &str {
+        &self.movetext
+    }
+
+    /// Returns the line in the file that the game starts on, the first line is 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Applies all moves in the movetext to a game
+    ///
+    /// # Returns
+    /// * `Result<PgnGame, ParsePgnError>` - The game, or an error if the movetext is invalid
+    pub fn replay(&self) -> Result<PgnGame, ParsePgnError> {
+        parse_game(self.tags.clone(), &mut Lexer::new(&self.movetext))
+    }
+}
+
+/// Reads games one by one from a PGN database
+///
+/// Only one game is held in memory at a time, so files of any size can be read. A game with
+/// malformed tags or a comment that runs into the next game is returned as an error and the
+/// reader continues with the next game, so one broken game doesn't stop the rest of the file from
+/// being read.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::PgnReader;
+///
+/// let pgn = "[White \"A\"]\n\n1. e4 e5 *\n\n[White \"B\"]\n\n1. d4 d5 *\n";
+///
+/// let players = PgnReader::new(pgn.as_bytes())
+///     .filter_map(|game| game.ok())
+///     .map(|game| game.tag("White").unwrap_or_default().to_string())
+///     .collect::<Vec<String>>();
+///
+/// assert_eq!(players, vec!["A", "B"]);
+/// ```
+pub struct PgnReader<R: BufRead> {
+    reader: R,
+    /// A line that has been read but belongs to the next game
+    next_line: Option<String>,
+    /// The number of the last line that was read
+    line: usize,
+    done: bool,
+}
+
+impl<R: BufRead> PgnReader<R> {
+    /// Creates a reader that reads games from a buffered reader, e.g a `BufReader<File>`
+    pub fn new(reader: R) -> PgnReader<R> {
+        PgnReader {
+            reader,
+            next_line: None,
+            line: 0,
+            done: false,
+        }
+    }
+
+    /// Internal helper that reads the next line, invalid UTF-8 is replaced instead of failing
+    fn read_line(&mut self) -> Result<Option<String>, std::io::Error> {
+        if let Some(line) = self.next_line.take() {
+            return Ok(Some(line));
+        }
+
+        let mut bytes = vec![];
+        if self.reader.read_until(b'\n', &mut bytes)? == 0 {
+            return Ok(None);
+        }
+        self.line += 1;
+
+        Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    /// Internal helper that reads the tag section and movetext of the next game
+    ///
+    /// The movetext ends at the first empty line or tag line outside of a comment. A comment that
+    /// is never closed would swallow the rest of the file, so a tag line after an empty line ends
+    /// the movetext even inside a comment.
+    ///
+    /// # Returns
+    /// * `Result<Option<(String, String, usize, bool)>, std::io::Error>` - The tag section, the
+    ///   movetext, the line the game starts on and if the movetext was cut off inside a comment
+    fn read_game(&mut self) -> Result<Option<(String, String, usize, bool)>, std::io::Error> {
+        let mut header = String::new();
+        let mut movetext = String::new();
+        let mut start_line = 0;
+        let mut in_comment = false;
+        let mut after_empty_line = false;
+
+        while let Some(line) = self.read_line()? {
+            let trimmed = line.trim();
+            let is_tag = trimmed.starts_with('[');
+
+            if in_comment && after_empty_line && is_tag_pair(trimmed) {
+                self.next_line = Some(line);
+                return Ok(Some((header, movetext, start_line, true)));
+            }
+            after_empty_line = trimmed.is_empty();
+
+            if !in_comment && movetext.trim().is_empty() {
+                if trimmed.is_empty() || trimmed.starts_with('%') {
+                    continue;
+                }
+
+                if is_tag {
+                    if start_line == 0 {
+                        start_line = self.line;
+                    }
+                    header.push_str(&line);
+                    continue;
+                }
+            } else if !in_comment && (trimmed.is_empty() || is_tag) {
+                if is_tag {
+                    self.next_line = Some(line);
+                }
+                break;
+            }
+
+            if start_line == 0 {
+                start_line = self.line;
+            }
+            in_comment = ends_in_comment(&line, in_comment);
+            movetext.push_str(&line);
+        }
+
+        if header.is_empty() && movetext.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some((header, movetext, start_line, false)))
+    }
+}
+
+impl<R: BufRead> Iterator for PgnReader<R> {
+    type Item = Result<RawPgnGame, ReadPgnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let (header, movetext, line, cut_off) = match self.read_game() {
+            Ok(Some(game)) => game,
+            Ok(None) => return None,
+            Err(err) => {
+                self.done = true;
+                return Some(Err(ReadPgnError::Io(err)));
+            }
+        };
+
+        if cut_off {
+            return Some(Err(ReadPgnError::MalformedGame {
+                line,
+                source: ParsePgnError::UnterminatedComment,
+            }));
+        }
+
+        let mut lexer = Lexer::new(&header);
+        let tags = parse_tags(&mut lexer).and_then(|tags| match lexer.next() {
+            None => Ok(tags),
+            Some(token) => Err(ParsePgnError::InvalidTag(token?.to_string())),
+        });
+
+        Some(
+            tags.map(|tags| RawPgnGame {
+                tags,
+                movetext,
+                line,
+            })
+            .map_err(|source| ReadPgnError::MalformedGame { line, source }),
+        )
+    }
+}
+
+/// Internal helper that returns if a line looks like a tag pair, e.g `[Event "Name"]`
+fn is_tag_pair(line: &str) -> bool {
+    line.strip_prefix('[')
+        .and_then(|line| line.strip_suffix(']'))
+        .is_some_and(|pair| {
+            pair.starts_with(|c: char| c.is_ascii_alphanumeric()) && pair.ends_with('"')
+        })
+}
+
+/// Internal helper that returns if a line of movetext ends inside a `{ ... }` comment
+fn ends_in_comment(line: &str, mut in_comment: bool) -> bool {
+    for c in line.chars() {
+        match c {
+            '{' if !in_comment => in_comment = true,
+            '}' if in_comment => in_comment = false,
+            ';' if !in_comment => return false,
+            _ => (),
+        }
+    }
+
+    in_comment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PgnResult;
+
+    const DATABASE: &str = r#"[Event "First"]
+[White "A"]
+
+1. e4 e5 2. Nf3 {A comment
+
+with an empty line} Nc6 1-0
+
+[Event "Broken tags"
+[White "B"]
+
+1. d4 d5 *
+
+[Event "Illegal move"]
+
+1. e4 e4 *
+[Event "No empty line before this game"]
+[FEN "4k3/8/8/8/8/8/8/R3K3 w Q - 0 1"]
+
+1. O-O-O *
+"#;
+
+    #[test]
+    fn should_stream_games_and_recover_from_errors() {
+        let games = PgnReader::new(DATABASE.as_bytes()).collect::<Vec<_>>();
+
+        assert_eq!(games.len(), 4);
+
+        let first = games[0].as_ref().unwrap();
+        assert_eq!(first.tag("White"), Some("A"));
+        assert_eq!(first.line(), 1);
+        let first = first.replay().unwrap();
+        assert_eq!(first.game().ply(), 4);
+        assert_eq!(first.result(), PgnResult::WhiteWins);
+
+        assert!(matches!(
+            games[1],
+            Err(ReadPgnError::MalformedGame { line: 8, .. })
+        ));
+
+        let illegal = games[2].as_ref().unwrap();
+        assert_eq!(illegal.tag("Event"), Some("Illegal move"));
+        assert!(matches!(
+            illegal.replay(),
+            Err(ParsePgnError::InvalidMove { move_number: 1, .. })
+        ));
+
+        let last = games[3].as_ref().unwrap();
+        assert_eq!(last.line(), 16);
+        assert_eq!(
+            last.replay().unwrap().game().fen(),
+            "4k3/8/8/8/8/8/8/2KR4 b - - 1 1"
+        );
+    }
+
+    #[test]
+    fn should_recover_from_a_comment_that_is_never_closed() {
+        let pgn = r#"[Event "Broken comment"]
+
+1. e4 {This comment is never closed e5 *
+
+[Event "Second"]
+
+1. d4 {A comment
+
+[%clk 0:01:00]} d5 *
+
+[Event "Third"]
+
+1. c4 *
+"#;
+
+        let games = PgnReader::new(pgn.as_bytes()).collect::<Vec<_>>();
+
+        assert_eq!(games.len(), 3);
+        assert!(matches!(
+            games[0],
+            Err(ReadPgnError::MalformedGame {
+                line: 1,
+                source: ParsePgnError::UnterminatedComment,
+            })
+        ));
+
+        let second = games[1].as_ref().unwrap();
+        assert_eq!(second.tag("Event"), Some("Second"));
+        assert_eq!(second.line(), 5);
+        assert_eq!(second.replay().unwrap().game().ply(), 2);
+
+        let third = games[2].as_ref().unwrap();
+        assert_eq!(third.tag("Event"), Some("Third"));
+        assert_eq!(third.replay().unwrap().game().ply(), 1);
+    }
+
+    #[test]
+    fn should_replace_invalid_utf8() {
+        let mut pgn = b"[White \"".to_vec();
+        pgn.extend_from_slice(&[0xff, 0xfe]);
+        pgn.extend_from_slice(b"\"]\n\n1. e4 *\n");
+
+        let games = PgnReader::new(pgn.as_slice()).collect::<Vec<_>>();
+
+        assert_eq!(games.len(), 1);
+        assert_eq!(
+            games[0].as_ref().unwrap().tag("White"),
+            Some("\u{fffd}\u{fffd}")
+        );
+    }
+}
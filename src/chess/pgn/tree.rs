@@ -0,0 +1,293 @@
+use std::str::FromStr;
+
+use super::{
+    append_comment,
+    export::{export_result, push_comment, tag_section, wrap, LINE_LENGTH},
+    parse_tags, starting_position, Lexer, Token,
+};
+use crate::{error::ParsePgnError, Color, GameTree, NodeId, PgnResult};
+
+impl FromStr for GameTree {
+    type Err = ParsePgnError;
+
+    /// Parses a single game in PGN, keeping variations, comments and annotations
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::GameTree;
+    ///
+    /// let tree = "1. e4 $1 {Best by test} (1. d4 d5) 1... e5 *".parse::<GameTree>().unwrap();
+    /// let root = tree.root();
+    ///
+    /// assert_eq!(tree.children(root).len(), 2);
+    /// assert_eq!(tree.comment(tree.children(root)[0]), Some("Best by test"));
+    /// assert_eq!(tree.main_line().len(), 2);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lexer = Lexer::new(s);
+
+        let tags = parse_tags(&mut lexer)?;
+        let mut tree = GameTree::new(starting_position(&tags)?);
+        let result = parse_variations(&mut lexer, &mut tree)?;
+
+        if let Some(token) = lexer.next() {
+            let last = tree.main_line().last().copied().unwrap_or(tree.root());
+
+            return Err(ParsePgnError::UnexpectedToken {
+                move_number: tree.position(last).get_fullmove_number(),
+                token: token?.to_string(),
+            });
+        }
+
+        tree.tags = tags;
+        tree.result = result;
+
+        Ok(tree)
+    }
+}
+
+impl GameTree {
+    /// Returns the tree in PGN export format
+    ///
+    /// The tags are written like in `PgnGame::to_pgn`. Variations are written in parentheses
+    /// after the move they replace and annotations as `$n`.
+    ///
+    /// # Returns
+    /// * `String` - The tree in PGN
+    pub fn to_pgn(&self) -> String {
+        let root = self.root();
+        let end = self.main_line().last().copied().unwrap_or(root);
+        let result = export_result(&self.game(end), self.result);
+
+        let mut pgn = tag_section(&self.tags, self.position(root), result);
+
+        let mut tokens = vec![];
+        if let Some(comment) = self.comment(root) {
+            push_comment(&mut tokens, comment);
+        }
+        self.variation_tokens(root, &mut tokens, true);
+        tokens.push(result.to_string());
+
+        pgn.push_str(&wrap(&tokens, LINE_LENGTH));
+        pgn.push('\n');
+
+        pgn
+    }
+
+    /// Internal helper that writes the line after `parent`, with every variation along it
+    ///
+    /// A black move needs a move number if it starts a line or comes after a comment or
+    /// variation.
+    fn variation_tokens(&self, mut parent: NodeId, tokens: &mut Vec<String>, needs_number: bool) {
+        let mut needs_number = needs_number;
+
+        while let Some((&main, variations)) = self.children(parent).split_first() {
+            self.move_tokens(main, tokens, needs_number);
+            needs_number = self.comment(main).is_some();
+
+            for &variation in variations {
+                let start = tokens.len();
+
+                if let Some(comment) = self.starting_comment(variation) {
+                    push_comment(tokens, comment);
+                }
+                self.move_tokens(variation, tokens, true);
+                self.variation_tokens(variation, tokens, self.comment(variation).is_some());
+
+                tokens[start].insert(0, '(');
+                tokens
+                    .last_mut()
+                    .expect("A variation has at least one move")
+                    .push(')');
+                needs_number = true;
+            }
+
+            parent = main;
+        }
+    }
+
+    /// Internal helper that writes the move of a node with its move number, NAGs and comment
+    fn move_tokens(&self, node: NodeId, tokens: &mut Vec<String>, needs_number: bool) {
+        let position = self.position(self.parent(node).expect("Only the root has no move"));
+        let mv = self.mv(node).expect("Only the root has no move");
+
+        let number = position.get_fullmove_number();
+        if position.get_turn() == Color::White {
+            tokens.push(format!("{}.", number));
+        } else if needs_number {
+            tokens.push(format!("{}...", number));
+        }
+
+        tokens.push(position.san(mv));
+        tokens.extend(self.nags(node).iter().map(|nag| format!("${}", nag)));
+
+        if let Some(comment) = self.comment(node) {
+            push_comment(tokens, comment);
+        }
+    }
+}
+
+/// Internal helper that reads movetext with variations into a tree
+///
+/// # Returns
+/// * `Result<PgnResult, ParsePgnError>` - The game termination marker, if the movetext ends
+///   without one the result is `PgnResult::Ongoing`
+fn parse_variations(lexer: &mut Lexer, tree: &mut GameTree) -> Result<PgnResult, ParsePgnError> {
+    let mut current = tree.root();
+    // The node to go back to when each open variation ends
+    let mut stack = vec![];
+    // A comment before the first move of a variation
+    let mut starting_comment: Option<String> = None;
+
+    for token in lexer.by_ref() {
+        let move_number = tree.position(current).get_fullmove_number();
+
+        match token? {
+            Token::MoveNumber(_) => (),
+            Token::Nag(nag) => tree.add_nag(current, nag),
+            Token::Comment(comment) => match starting_comment.as_mut() {
+                Some(existing) => append_comment(existing, comment),
+                None => {
+                    let mut existing = tree.comment(current).unwrap_or_default().to_string();
+                    append_comment(&mut existing, comment);
+                    tree.set_comment(current, &existing);
+                }
+            },
+            Token::VariationStart if tree.parent(current).is_some() => {
+                stack.push(current);
+                current = tree.parent(current).expect("Checked above");
+                starting_comment = Some(String::new());
+            }
+            Token::VariationEnd if !stack.is_empty() => {
+                current = stack.pop().expect("Checked above");
+                starting_comment = None;
+            }
+            Token::Symbol(san) => {
+                let mv = tree.position(current).parse_san(san).map_err(|source| {
+                    ParsePgnError::InvalidMove {
+                        move_number,
+                        token: san.to_string(),
+                        source,
+                    }
+                })?;
+
+                // The first move of a variation always gets its own node, even if it's the same
+                // move as the one the variation is an alternative to
+                let node = if starting_comment.is_some() {
+                    tree.add_new_move(current, mv)
+                } else {
+                    tree.add_move(current, mv)
+                }
+                .expect("parse_san only returns legal moves");
+
+                if let Some(comment) = starting_comment.take() {
+                    tree.set_starting_comment(node, &comment);
+                }
+                current = node;
+            }
+            Token::Result(result) if stack.is_empty() => return Ok(result),
+            token => {
+                return Err(ParsePgnError::UnexpectedToken {
+                    move_number,
+                    token: token.to_string(),
+                })
+            }
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(ParsePgnError::UnterminatedVariation);
+    }
+
+    Ok(PgnResult::Ongoing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseSanError;
+
+    #[test]
+    fn should_round_trip_variations() {
+        let pgn = "[Event \"Variations\"]\n\n1. e4 $1 {The king's pawn} (1. d4 d5 (1... Nf6 2. c4 \
+                   $2) 2. c4) (1. c4) 1... e5 2. Nf3 ({Or} 2. f4 exf4) 2... Nc6 *";
+
+        let tree = pgn.parse::<GameTree>().unwrap();
+        let root = tree.root();
+
+        let [e4, d4, c4] = tree.children(root) else {
+            panic!("Expected three first moves");
+        };
+        assert_eq!(tree.nags(*e4), &[1]);
+        assert_eq!(tree.comment(*e4), Some("The king's pawn"));
+        assert_eq!(tree.children(*d4).len(), 2);
+        assert!(tree.children(*c4).is_empty());
+
+        let f4 = tree.children(tree.main_line()[1])[1];
+        assert_eq!(tree.starting_comment(f4), Some("Or"));
+
+        assert_eq!(
+            tree.to_pgn(),
+            "[Event \"Variations\"]\n\
+             [Site \"?\"]\n\
+             [Date \"????.??.??\"]\n\
+             [Round \"?\"]\n\
+             [White \"?\"]\n\
+             [Black \"?\"]\n\
+             [Result \"*\"]\n\
+             \n\
+             1. e4 $1 {The king's pawn} (1. d4 d5 (1... Nf6 2. c4 $2) 2. c4) (1. c4) 1... e5\n\
+             2. Nf3 ({Or} 2. f4 exf4) 2... Nc6 *\n"
+        );
+        assert_eq!(
+            tree.to_pgn().parse::<GameTree>().unwrap().to_pgn(),
+            tree.to_pgn()
+        );
+    }
+
+    #[test]
+    fn should_keep_variations_that_repeat_the_main_line() {
+        let pgn = "1. e4 {Main} ({Again} 1. e4 e5) 1... e6 *";
+
+        let tree = pgn.parse::<GameTree>().unwrap();
+        let [main, variation] = tree.children(tree.root()) else {
+            panic!("Expected two first moves");
+        };
+        assert_eq!(tree.mv(*main), tree.mv(*variation));
+        assert_eq!(tree.comment(*main), Some("Main"));
+        assert_eq!(tree.starting_comment(*main), None);
+        assert_eq!(tree.starting_comment(*variation), Some("Again"));
+        assert_eq!(tree.children(*variation).len(), 1);
+
+        assert_eq!(
+            tree.to_pgn().parse::<GameTree>().unwrap().to_pgn(),
+            tree.to_pgn()
+        );
+        assert!(tree
+            .to_pgn()
+            .ends_with("1. e4 {Main} ({Again} 1. e4 e5) 1... e6 *\n"));
+    }
+
+    #[test]
+    fn should_reject_broken_variations() {
+        assert!(matches!(
+            "(1. e4) *".parse::<GameTree>(),
+            Err(ParsePgnError::UnexpectedToken { .. })
+        ));
+        assert!(matches!(
+            "1. e4 (1. d4 *".parse::<GameTree>(),
+            Err(ParsePgnError::UnexpectedToken { .. })
+        ));
+        assert!(matches!(
+            "1. e4 (1. d4".parse::<GameTree>(),
+            Err(ParsePgnError::UnterminatedVariation)
+        ));
+        assert!(matches!(
+            "1. e4 (1. e5)".parse::<GameTree>(),
+            Err(ParsePgnError::InvalidMove {
+                source: ParseSanError::IllegalMove,
+                ..
+            })
+        ));
+    }
+}
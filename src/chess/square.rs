@@ -0,0 +1,328 @@
+use std::str::FromStr;
+
+use crate::error::{CoordinateError, ParseSquareError};
+
+/// A column of the board, `a` to `h`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct File(u8);
+
+/// A row of the board, `1` to `8`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rank(u8);
+
+/// A square on the board, e.g `e4`
+///
+/// The rest of the library uses `(x, y)` tuples for squares, where `x` is the file and `y` is
+/// the rank counted from the top of the board, so `(0, 0)` is `a8` and `(4, 4)` is `e4`. A square
+/// can be converted to and from such a tuple with `From` and `TryFrom`.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{File, Rank, Square};
+///
+/// let square = "e4".parse::<Square>().unwrap();
+///
+/// assert_eq!(square, Square::E4);
+/// assert_eq!(square, Square::new(File::E, Rank::FOURTH));
+/// assert_eq!(square.to_string(), "e4");
+/// assert_eq!(<(usize, usize)>::from(square), (4, 4));
+/// assert_eq!(Square::try_from((0, 0)).unwrap(), Square::A8);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Square(u8);
+
+impl File {
+    pub const A: File = File(0);
+    pub const B: File = File(1);
+    pub const C: File = File(2);
+    pub const D: File = File(3);
+    pub const E: File = File(4);
+    pub const F: File = File(5);
+    pub const G: File = File(6);
+    pub const H: File = File(7);
+
+    /// Returns the file with an index, 0 is the `a` file
+    pub const fn new(index: usize) -> Option<File> {
+        if index < 8 {
+            Some(File(index as u8))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the file of a character, e.g `'c'`
+    pub const fn from_char(c: char) -> Option<File> {
+        match c {
+            'a'..='h' => Some(File(c as u8 - b'a')),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the file, 0 is the `a` file. This is also the x coordinate of the file
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Returns the character of the file, e.g `'c'`
+    pub const fn to_char(self) -> char {
+        (b'a' + self.0) as char
+    }
+
+    /// Returns all files from `a` to `h`
+    pub fn iter() -> impl DoubleEndedIterator<Item = File> {
+        (0..8).map(File)
+    }
+}
+
+impl Rank {
+    pub const FIRST: Rank = Rank(0);
+    pub const SECOND: Rank = Rank(1);
+    pub const THIRD: Rank = Rank(2);
+    pub const FOURTH: Rank = Rank(3);
+    pub const FIFTH: Rank = Rank(4);
+    pub const SIXTH: Rank = Rank(5);
+    pub const SEVENTH: Rank = Rank(6);
+    pub const EIGHTH: Rank = Rank(7);
+
+    /// Returns the rank with an index, 0 is the first rank
+    pub const fn new(index: usize) -> Option<Rank> {
+        if index < 8 {
+            Some(Rank(index as u8))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the rank of a character, e.g `'4'`
+    pub const fn from_char(c: char) -> Option<Rank> {
+        match c {
+            '1'..='8' => Some(Rank(c as u8 - b'1')),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the rank, 0 is the first rank
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Returns the y coordinate of the rank, 0 is the eighth rank
+    pub const fn y(self) -> usize {
+        7 - self.0 as usize
+    }
+
+    /// Returns the character of the rank, e.g `'4'`
+    pub const fn to_char(self) -> char {
+        (b'1' + self.0) as char
+    }
+
+    /// Returns all ranks from the first to the eighth
+    pub fn iter() -> impl DoubleEndedIterator<Item = Rank> {
+        (0..8).map(Rank)
+    }
+}
+
+/// Internal helper that defines a constant for every square
+macro_rules! square_consts {
+    ($($name:ident = $index:expr),* $(,)?) => {
+        $(pub const $name: Square = Square($index);)*
+    };
+}
+
+impl Square {
+    square_consts! {
+        A1 = 0, B1 = 1, C1 = 2, D1 = 3, E1 = 4, F1 = 5, G1 = 6, H1 = 7,
+        A2 = 8, B2 = 9, C2 = 10, D2 = 11, E2 = 12, F2 = 13, G2 = 14, H2 = 15,
+        A3 = 16, B3 = 17, C3 = 18, D3 = 19, E3 = 20, F3 = 21, G3 = 22, H3 = 23,
+        A4 = 24, B4 = 25, C4 = 26, D4 = 27, E4 = 28, F4 = 29, G4 = 30, H4 = 31,
+        A5 = 32, B5 = 33, C5 = 34, D5 = 35, E5 = 36, F5 = 37, G5 = 38, H5 = 39,
+        A6 = 40, B6 = 41, C6 = 42, D6 = 43, E6 = 44, F6 = 45, G6 = 46, H6 = 47,
+        A7 = 48, B7 = 49, C7 = 50, D7 = 51, E7 = 52, F7 = 53, G7 = 54, H7 = 55,
+        A8 = 56, B8 = 57, C8 = 58, D8 = 59, E8 = 60, F8 = 61, G8 = 62, H8 = 63,
+    }
+
+    /// Returns the square on a file and rank
+    pub const fn new(file: File, rank: Rank) -> Square {
+        Square(rank.0 * 8 + file.0)
+    }
+
+    /// Returns the square with an index, 0 is `a1`, 1 is `b1` and 63 is `h8`
+    pub const fn from_index(index: usize) -> Option<Square> {
+        if index < 64 {
+            Some(Square(index as u8))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the square at `(x, y)` coordinates, or None if they are outside the board
+    pub const fn from_coords(x: usize, y: usize) -> Option<Square> {
+        if x < 8 && y < 8 {
+            Some(Square(((7 - y) * 8 + x) as u8))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the index of the square, 0 is `a1`, 1 is `b1` and 63 is `h8`
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Returns the file of the square
+    pub const fn file(self) -> File {
+        File(self.0 % 8)
+    }
+
+    /// Returns the rank of the square
+    pub const fn rank(self) -> Rank {
+        Rank(self.0 / 8)
+    }
+
+    /// Returns the `(x, y)` coordinates of the square
+    pub const fn coords(self) -> (usize, usize) {
+        (self.file().index(), self.rank().y())
+    }
+
+    /// Returns the square a number of files and ranks away, or None if it's outside the board
+    ///
+    /// # Arguments
+    /// * `files` - The number of files to move, positive is towards the `h` file
+    /// * `ranks` - The number of ranks to move, positive is towards the eighth rank
+    pub const fn offset(self, files: isize, ranks: isize) -> Option<Square> {
+        let file = self.file().0 as isize + files;
+        let rank = self.rank().0 as isize + ranks;
+
+        if file < 0 || file > 7 || rank < 0 || rank > 7 {
+            None
+        } else {
+            Some(Square((rank * 8 + file) as u8))
+        }
+    }
+
+    /// Returns all squares from `a1` to `h8`, rank by rank
+    pub fn iter() -> impl DoubleEndedIterator<Item = Square> {
+        (0..64).map(Square)
+    }
+}
+
+impl From<Square> for (usize, usize) {
+    fn from(square: Square) -> Self {
+        square.coords()
+    }
+}
+
+impl TryFrom<(usize, usize)> for Square {
+    type Error = CoordinateError;
+
+    fn try_from((x, y): (usize, usize)) -> Result<Self, Self::Error> {
+        Square::from_coords(x, y).ok_or(CoordinateError::OutOfBounds { x, y })
+    }
+}
+
+impl FromStr for File {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => File::from_char(c).ok_or(ParseSquareError::InvalidFile(c)),
+            _ => Err(ParseSquareError::Malformed(s.to_string())),
+        }
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Rank::from_char(c).ok_or(ParseSquareError::InvalidRank(c)),
+            _ => Err(ParseSquareError::Malformed(s.to_string())),
+        }
+    }
+}
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(file), Some(rank), None) => {
+                let file = File::from_char(file).ok_or(ParseSquareError::InvalidFile(file))?;
+                let rank = Rank::from_char(rank).ok_or(ParseSquareError::InvalidRank(rank))?;
+
+                Ok(Square::new(file, rank))
+            }
+            _ => Err(ParseSquareError::Malformed(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for File {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl std::fmt::Display for Rank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl std::fmt::Display for Square {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.file(), self.rank())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_and_display_squares() {
+        for square in Square::iter() {
+            assert_eq!(square.to_string().parse::<Square>().unwrap(), square);
+        }
+
+        assert_eq!(Square::iter().next_back(), Some(Square::H8));
+        assert_eq!("a1".parse::<Square>().unwrap().index(), 0);
+        assert!(matches!(
+            "i1".parse::<Square>(),
+            Err(ParseSquareError::InvalidFile('i'))
+        ));
+        assert!(matches!(
+            "a9".parse::<Square>(),
+            Err(ParseSquareError::InvalidRank('9'))
+        ));
+        assert!(matches!(
+            "e44".parse::<Square>(),
+            Err(ParseSquareError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn should_convert_to_and_from_coordinates() {
+        for x in 0..8 {
+            for y in 0..8 {
+                let square = Square::try_from((x, y)).unwrap();
+                assert_eq!(square.coords(), (x, y));
+            }
+        }
+
+        assert_eq!(Square::E2.coords(), (4, 6));
+        assert_eq!(Square::H1.offset(-7, 7), Some(Square::A8));
+        assert_eq!(Square::H1.offset(1, 0), None);
+        assert!(matches!(
+            Square::try_from((8, 0)),
+            Err(CoordinateError::OutOfBounds { x: 8, y: 0 })
+        ));
+    }
+}
@@ -13,16 +13,18 @@
 //! with a valid move enum.
 //!
 //! The idea on how to play a game of chess with this library:
-//! - Start by checking `is_checkmate` and `is_stalemate` to see if the game has ended
+//! - Start by checking `outcome` to see if the game has ended and why
 //! - Call either `gen_moves` or `gen_all_moves` to get a vector containing all the moves for the
-//! current turn
+//!   current turn, or `generate_legal_into` to fill a reusable `MoveList` without allocating
 //! - Pick a move from the vector and apply it to the game with `apply_move`
 //! - Repeat 🔁
 //!
 //! ## Things that are not implemented by design 🚫
 //!
 //! - There is no real way to switch turns in the game.
-//! - There is no way to move pieces arbitrarily around in a game.
+//! - There is no way to move pieces arbitrarily around in a game. To set up a position, e.g for
+//!   a puzzle, use `PositionBuilder`, which checks that the position is valid before it gives you
+//!   a `Game`.
 //!
 //! ## If you have any questions or suggestions 🤔
 //!
@@ -41,69 +43,98 @@ pub use crate::chess::*;
 mod tests {
     use super::*;
 
-    /// Function that searches game recursively for moves
-    /// Used for perft testing
-    fn amount_of_moves_recursively(game: Game, depth: u8) -> u64 {
-        if depth == 0 {
-            return 1;
-        }
-        let mut amount = 0;
-        for m in game.gen_all_moves().unwrap_or(Vec::new()) {
-            let mut game = game.clone();
-            game.apply_move(m).unwrap();
-            amount += amount_of_moves_recursively(game, depth - 1);
-        }
-        amount
-    }
-
     #[test]
-    #[ignore]
     fn perft_1() {
         let game = Game::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - -").unwrap();
-        let amount_of_moves = amount_of_moves_recursively(game, 3);
+        let amount_of_moves = game.perft(3);
         assert_eq!(amount_of_moves, 2812);
     }
 
     #[test]
-    #[ignore]
     fn perft_2() {
         let game =
             Game::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq -").unwrap();
-        let amount_of_moves = amount_of_moves_recursively(game, 3);
+        let amount_of_moves = game.perft(3);
         assert_eq!(amount_of_moves, 9467);
     }
 
     #[test]
-    #[ignore]
     fn perft_3() {
         let game =
             Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq -")
                 .unwrap();
-        let amount_of_moves = amount_of_moves_recursively(game, 3);
+        let amount_of_moves = game.perft(3);
         assert_eq!(amount_of_moves, 97862);
     }
 
     #[test]
-    #[ignore]
     fn perft_4() {
         let game = Game::from_fen("r3k2r/8/3Q4/8/8/5q2/8/R3K2R b KQkq -").unwrap();
-        let amount_of_moves = amount_of_moves_recursively(game, 4);
+        let amount_of_moves = game.perft(4);
         assert_eq!(amount_of_moves, 1720476);
     }
 
     #[test]
-    #[ignore]
     fn perft_5() {
         let game = Game::from_fen("8/8/1P2K3/8/2n5/1q6/8/5k2 b - -").unwrap();
-        let amount_of_moves = amount_of_moves_recursively(game, 5);
+        let amount_of_moves = game.perft(5);
         assert_eq!(amount_of_moves, 1004658);
     }
 
     #[test]
-    #[ignore]
     fn perft_6() {
         let game = Game::from_fen("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ -").unwrap();
-        let amount_of_moves = amount_of_moves_recursively(game, 3);
+        let amount_of_moves = game.perft(3);
         assert_eq!(amount_of_moves, 62379);
     }
+
+    #[test]
+    fn perft_divide_should_add_up_to_perft() {
+        let game =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq -")
+                .unwrap();
+        let divide = game.perft_divide(2);
+
+        assert_eq!(divide.len(), 48);
+        assert_eq!(divide.iter().map(|(_, count)| count).sum::<u64>(), 2039);
+        assert!(divide.contains(&("e1g1".to_string(), 43)));
+    }
+
+    #[test]
+    fn parallel_cached_perft_should_match_perft() {
+        let game =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq -")
+                .unwrap();
+        let result = Perft::new(3).set_threads(4).set_cache_size(1).run(&game);
+
+        assert_eq!(result.nodes, 97862);
+        assert_eq!(result.divide, game.perft_divide(3));
+        assert_eq!(Perft::new(0).run(&game).nodes, 1);
+    }
+
+    #[test]
+    fn cached_perft_should_match_uncached_perft() {
+        let game =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq -")
+                .unwrap();
+        // A small cache so entries are overwritten while the threads read them
+        let cached = Perft::new(4).set_threads(4).set_cache_size(1).run(&game);
+        let uncached = Perft::new(4).set_threads(4).run(&game);
+
+        assert_eq!(cached.nodes, 4085603);
+        assert_eq!(cached.divide, uncached.divide);
+    }
+
+    #[test]
+    #[ignore]
+    fn cached_perft_should_match_uncached_perft_deep() {
+        let game =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq -")
+                .unwrap();
+        let cached = Perft::new(5).set_threads(0).set_cache_size(16).run(&game);
+        let uncached = Perft::new(5).set_threads(0).run(&game);
+
+        assert_eq!(cached.nodes, 193690690);
+        assert_eq!(cached.divide, uncached.divide);
+    }
 }